@@ -0,0 +1,10 @@
+-- Baseline schema for the test resource (no-op on databases that already have it)
+CREATE SCHEMA IF NOT EXISTS comic;
+
+CREATE TABLE IF NOT EXISTS comic.test (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    title TEXT NOT NULL,
+    content TEXT,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP
+);
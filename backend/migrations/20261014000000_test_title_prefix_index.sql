@@ -0,0 +1,3 @@
+-- Supports prefix matching for GET /api/tests/suggest (lower(title) LIKE 'q%')
+CREATE INDEX IF NOT EXISTS test_title_lower_prefix_idx
+    ON comic.test (lower(title) text_pattern_ops);
@@ -0,0 +1,14 @@
+-- Keep updated_at current on every UPDATE so handlers don't have to set it.
+-- New tables should install the same trigger.
+CREATE OR REPLACE FUNCTION comic.set_updated_at() RETURNS TRIGGER AS $$
+BEGIN
+    NEW.updated_at = CURRENT_TIMESTAMP;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS test_set_updated_at ON comic.test;
+CREATE TRIGGER test_set_updated_at
+    BEFORE UPDATE ON comic.test
+    FOR EACH ROW
+    EXECUTE FUNCTION comic.set_updated_at();
@@ -0,0 +1,16 @@
+-- Snapshot of a test after each write, for GET /api/tests/:id/versions.
+-- Rows are never updated, so there is no updated_at trigger.
+CREATE TABLE IF NOT EXISTS comic.test_versions (
+    test_id UUID NOT NULL REFERENCES comic.test (id) ON DELETE CASCADE,
+    version INTEGER NOT NULL,
+    title TEXT NOT NULL,
+    content TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (test_id, version)
+);
+
+-- Existing tests start their history at their current state
+INSERT INTO comic.test_versions (test_id, version, title, content, created_at)
+SELECT id, 1, title, content, COALESCE(updated_at, created_at, CURRENT_TIMESTAMP)
+FROM comic.test
+ON CONFLICT (test_id, version) DO NOTHING;
@@ -0,0 +1,4 @@
+-- Supports the exact-title lookup in PUT /api/tests/by-title/:title, which
+-- runs under an advisory lock and picks the oldest test with the title
+CREATE INDEX IF NOT EXISTS test_title_created_at_idx
+    ON comic.test (title, created_at);
@@ -1,39 +1,176 @@
 use std::env;
-use tracing::info;
+use std::thread;
+use std::time::Duration;
+use std::{fmt, ops::Deref};
+use sqlx::postgres::PgSslMode;
+use tracing::{info, warn};
+use url::Url;
 
-#[derive(Clone, Debug)]
-pub struct Config {
-    pub database_url: String,
-    pub server_host: String,
-    pub server_port: u16,
-}
+// Database connection URL whose Debug output masks the password
+#[derive(Clone)]
+pub struct DatabaseUrl(String);
 
-impl Config {
-    pub fn from_env() -> Self {
-        dotenv::dotenv().ok();
-
-        // Build DATABASE_URL from individual components
+impl DatabaseUrl {
+    fn from_parts() -> Self {
         let postgres_host = env::var("POSTGRES_HOST")
             .unwrap_or_else(|_| "host.docker.internal".to_string());
         let postgres_port = env::var("POSTGRES_PORT")
             .unwrap_or_else(|_| "5433".to_string());
         let postgres_database = env::var("POSTGRES_DATABASE")
-            .expect("POSTGRES_DATABASE must be set");
+            .expect("POSTGRES_DATABASE must be set (or set DATABASE_URL)");
         let postgres_user = env::var("POSTGRES_USER")
-            .expect("POSTGRES_USER must be set");
+            .expect("POSTGRES_USER must be set (or set DATABASE_URL)");
         let postgres_password = env::var("POSTGRES_PASSWORD")
-            .expect("POSTGRES_PASSWORD must be set");
+            .expect("POSTGRES_PASSWORD must be set (or set DATABASE_URL)");
 
-        let database_url = format!(
+        Self(format!(
             "postgresql://{}:{}@{}:{}/{}",
             postgres_user, postgres_password, postgres_host, postgres_port, postgres_database
-        );
+        ))
+    }
+
+    // The URL with the password replaced by ****, wherever it appears:
+    // in the userinfo or as a `password` query parameter (both accepted by sqlx)
+    fn masked(&self) -> String {
+        let Ok(mut url) = Url::parse(&self.0) else {
+            // Don't echo what we can't parse; it may still contain a password
+            return "<unparseable database URL>".to_string();
+        };
+
+        if url.password().is_some() {
+            let _ = url.set_password(Some("****"));
+        }
+
+        if url.query_pairs().any(|(key, _)| key == "password") {
+            let pairs: Vec<(String, String)> = url
+                .query_pairs()
+                .map(|(key, value)| {
+                    let value = if key == "password" { "****".into() } else { value };
+                    (key.into_owned(), value.into_owned())
+                })
+                .collect();
+            url.query_pairs_mut().clear().extend_pairs(pairs);
+        }
+
+        url.to_string()
+    }
+}
+
+impl Deref for DatabaseUrl {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Debug for DatabaseUrl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.masked())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Environment {
+    Development,
+    Staging,
+    Production,
+}
+
+impl Environment {
+    // APP_ENV is required: defaulting would let a deployment that forgot
+    // to set it run with development behaviour
+    fn from_env() -> Self {
+        let value = env::var("APP_ENV")
+            .expect("APP_ENV must be set to development, staging or production");
+
+        Environment::parse(&value)
+            .unwrap_or_else(|| panic!("APP_ENV must be development, staging or production, got {}", value))
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "development" | "dev" => Some(Environment::Development),
+            "staging" => Some(Environment::Staging),
+            "production" | "prod" => Some(Environment::Production),
+            _ => None,
+        }
+    }
+
+    pub fn is_production(self) -> bool {
+        self == Environment::Production
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct TlsConfig {
+    pub cert_path: String,
+    pub key_path: String,
+}
+
+// Per-field size caps applied when validating test payloads
+#[derive(Clone, Debug)]
+pub struct FieldLimits {
+    pub title_max_bytes: usize,
+    pub content_max_bytes: usize,
+}
+
+#[derive(Clone, Debug)]
+pub struct Config {
+    pub environment: Environment,
+    pub database_url: DatabaseUrl,
+    pub server_host: String,
+    pub server_port: u16,
+    pub tls: Option<TlsConfig>,
+    pub worker_threads: usize,
+    pub db_slow_acquire_threshold: Duration,
+    pub db_max_lifetime: Duration,
+    pub db_idle_timeout: Duration,
+    pub db_test_before_acquire: bool,
+    // None keeps the sslmode from DATABASE_URL (sqlx defaults to prefer)
+    pub db_ssl_mode: Option<PgSslMode>,
+    pub db_ssl_root_cert: Option<String>,
+    pub field_limits: FieldLimits,
+    pub content_preview_chars: i32,
+    pub heavy_endpoint_concurrency: usize,
+    pub heavy_endpoint_queue_timeout: Duration,
+    pub max_concurrent_requests: usize,
+    pub max_body_bytes: usize,
+    pub maintenance_mode: bool,
+    pub maintenance_retry_after_secs: u64,
+    pub readiness_cache_ttl: Duration,
+    pub health_check_path: String,
+    pub auto_migrate: bool,
+    pub db_log_queries: bool,
+}
+
+impl Config {
+    pub fn from_env() -> Self {
+        dotenv::dotenv().ok();
+
+        // Prefer a single DATABASE_URL (as injected by most PaaS providers),
+        // otherwise build it from individual components
+        let database_url = match env::var("DATABASE_URL") {
+            Ok(url) if !url.is_empty() => DatabaseUrl(url),
+            _ => DatabaseUrl::from_parts(),
+        };
 
         // Log the database connection info (masking password)
-        info!("Database URL constructed: postgresql://{}:****@{}:{}/{}",
-            postgres_user, postgres_host, postgres_port, postgres_database);
+        info!("Database URL resolved: {:?}", database_url);
+
+        let environment = Environment::from_env();
+
+        // Query logging is a debugging aid and must never run in production
+        let db_log_queries = env::var("DB_LOG_QUERIES")
+            .unwrap_or_else(|_| "false".to_string())
+            .parse()
+            .expect("DB_LOG_QUERIES must be true or false");
+        if db_log_queries && environment.is_production() {
+            warn!("DB_LOG_QUERIES is ignored in production");
+        }
 
         Self {
+            environment,
             database_url,
             server_host: env::var("SERVER_HOST")
                 .unwrap_or_else(|_| "0.0.0.0".to_string()),
@@ -41,6 +178,222 @@ impl Config {
                 .unwrap_or_else(|_| "8080".to_string())
                 .parse()
                 .expect("SERVER_PORT must be a valid u16"),
+            tls: match (env::var("TLS_CERT_PATH").ok(), env::var("TLS_KEY_PATH").ok()) {
+                (Some(cert_path), Some(key_path)) => Some(TlsConfig {
+                    cert_path,
+                    key_path,
+                }),
+                (None, None) => None,
+                _ => panic!("TLS_CERT_PATH and TLS_KEY_PATH must be set together to enable TLS"),
+            },
+            worker_threads: env::var("WORKER_THREADS")
+                .ok()
+                .map(|threads| {
+                    threads
+                        .parse()
+                        .ok()
+                        .filter(|&threads: &usize| threads > 0)
+                        .expect("WORKER_THREADS must be a positive integer")
+                })
+                .unwrap_or_else(|| thread::available_parallelism().map_or(1, |n| n.get())),
+            db_slow_acquire_threshold: Duration::from_millis(
+                env::var("DB_SLOW_ACQUIRE_MS")
+                    .unwrap_or_else(|_| "200".to_string())
+                    .parse()
+                    .expect("DB_SLOW_ACQUIRE_MS must be a valid u64"),
+            ),
+            db_max_lifetime: Duration::from_secs(
+                env::var("DB_MAX_LIFETIME_SECS")
+                    .unwrap_or_else(|_| "1800".to_string())
+                    .parse()
+                    .expect("DB_MAX_LIFETIME_SECS must be a valid u64"),
+            ),
+            db_idle_timeout: Duration::from_secs(
+                env::var("DB_IDLE_TIMEOUT_SECS")
+                    .unwrap_or_else(|_| "600".to_string())
+                    .parse()
+                    .expect("DB_IDLE_TIMEOUT_SECS must be a valid u64"),
+            ),
+            db_test_before_acquire: env::var("DB_TEST_BEFORE_ACQUIRE")
+                .unwrap_or_else(|_| "true".to_string())
+                .parse()
+                .expect("DB_TEST_BEFORE_ACQUIRE must be true or false"),
+            db_ssl_mode: env::var("DB_SSLMODE").ok().filter(|mode| !mode.is_empty()).map(|mode| {
+                mode.parse().expect(
+                    "DB_SSLMODE must be one of disable, allow, prefer, require, verify-ca, verify-full",
+                )
+            }),
+            db_ssl_root_cert: env::var("DB_SSL_ROOT_CERT").ok().filter(|path| !path.is_empty()),
+            field_limits: FieldLimits {
+                title_max_bytes: env::var("TEST_TITLE_MAX_BYTES")
+                    .unwrap_or_else(|_| "255".to_string())
+                    .parse()
+                    .expect("TEST_TITLE_MAX_BYTES must be a valid usize"),
+                content_max_bytes: env::var("TEST_CONTENT_MAX_BYTES")
+                    .unwrap_or_else(|_| "51200".to_string())
+                    .parse()
+                    .expect("TEST_CONTENT_MAX_BYTES must be a valid usize"),
+            },
+            content_preview_chars: {
+                let chars: i32 = env::var("TEST_CONTENT_PREVIEW_CHARS")
+                    .unwrap_or_else(|_| "200".to_string())
+                    .parse()
+                    .expect("TEST_CONTENT_PREVIEW_CHARS must be a valid i32");
+                assert!(chars >= 0, "TEST_CONTENT_PREVIEW_CHARS must not be negative");
+                chars
+            },
+            heavy_endpoint_concurrency: env::var("HEAVY_ENDPOINT_CONCURRENCY")
+                .unwrap_or_else(|_| "16".to_string())
+                .parse()
+                .ok()
+                .filter(|&limit: &usize| limit > 0)
+                .expect("HEAVY_ENDPOINT_CONCURRENCY must be a positive integer"),
+            heavy_endpoint_queue_timeout: Duration::from_millis(
+                env::var("HEAVY_ENDPOINT_QUEUE_TIMEOUT_MS")
+                    .unwrap_or_else(|_| "500".to_string())
+                    .parse()
+                    .expect("HEAVY_ENDPOINT_QUEUE_TIMEOUT_MS must be a valid u64"),
+            ),
+            max_concurrent_requests: env::var("MAX_CONCURRENT_REQUESTS")
+                .unwrap_or_else(|_| "512".to_string())
+                .parse()
+                .ok()
+                .filter(|&limit: &usize| limit > 0)
+                .expect("MAX_CONCURRENT_REQUESTS must be a positive integer"),
+            max_body_bytes: env::var("MAX_BODY_BYTES")
+                .unwrap_or_else(|_| "2097152".to_string())
+                .parse()
+                .ok()
+                .filter(|&bytes: &usize| bytes > 0)
+                .expect("MAX_BODY_BYTES must be a positive integer"),
+            maintenance_mode: env::var("MAINTENANCE_MODE")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .expect("MAINTENANCE_MODE must be true or false"),
+            maintenance_retry_after_secs: env::var("MAINTENANCE_RETRY_AFTER_SECS")
+                .unwrap_or_else(|_| "300".to_string())
+                .parse()
+                .expect("MAINTENANCE_RETRY_AFTER_SECS must be a valid u64"),
+            readiness_cache_ttl: Duration::from_millis(
+                env::var("READINESS_CACHE_MS")
+                    .unwrap_or_else(|_| "1000".to_string())
+                    .parse()
+                    .expect("READINESS_CACHE_MS must be a valid u64"),
+            ),
+            health_check_path: {
+                let path = env::var("HEALTH_CHECK_PATH").unwrap_or_else(|_| "/health".to_string());
+                if let Err(reason) = check_health_check_path(&path) {
+                    panic!("HEALTH_CHECK_PATH={} is invalid: {}", path, reason);
+                }
+                path
+            },
+            auto_migrate: env::var("AUTO_MIGRATE")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .expect("AUTO_MIGRATE must be true or false"),
+            db_log_queries: db_log_queries && !environment.is_production(),
         }
     }
-}
\ No newline at end of file
+}
+
+// HEALTH_CHECK_PATH is registered as an extra route next to the rest of
+// the router, so it can't reuse one of its paths or live under /api
+fn check_health_check_path(path: &str) -> Result<(), &'static str> {
+    if !path.starts_with('/') {
+        return Err("must start with /");
+    }
+    if path.len() > 1 && path.ends_with('/') {
+        return Err("must not end with / (trailing slashes are stripped before routing)");
+    }
+    if path.contains([':', '*']) {
+        return Err("must be a literal path without : or * segments");
+    }
+    if path == "/ready" || path == "/api" || path.starts_with("/api/") {
+        return Err("conflicts with an API or readiness route");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_health_check_path, DatabaseUrl, Environment};
+
+    fn masked(url: &str) -> String {
+        DatabaseUrl(url.to_string()).masked()
+    }
+
+    #[test]
+    fn masks_userinfo_password() {
+        assert_eq!(
+            masked("postgresql://app:secret@db:5432/parapara"),
+            "postgresql://app:****@db:5432/parapara"
+        );
+    }
+
+    #[test]
+    fn masks_password_query_parameter() {
+        let url = masked("postgres:///parapara?host=/run/pg&password=secret&sslmode=require");
+        assert!(!url.contains("secret"), "{}", url);
+        assert!(url.contains("password=****"), "{}", url);
+        assert!(url.contains("sslmode=require"), "{}", url);
+    }
+
+    #[test]
+    fn masks_both_forms_together() {
+        let url = masked("postgres://app:secret@db/parapara?password=other");
+        assert!(!url.contains("secret") && !url.contains("other"), "{}", url);
+    }
+
+    #[test]
+    fn at_sign_in_query_is_not_treated_as_userinfo() {
+        let url = masked("postgres://db?user=app@example&password=secret");
+        assert!(url.starts_with("postgres://db?"), "{}", url);
+        assert!(!url.contains("secret"), "{}", url);
+    }
+
+    #[test]
+    fn leaves_url_without_password_alone() {
+        assert_eq!(masked("postgres://app@db/parapara"), "postgres://app@db/parapara");
+    }
+
+    #[test]
+    fn never_echoes_unparseable_urls() {
+        assert_eq!(masked("not a url :secret@"), "<unparseable database URL>");
+    }
+
+    #[test]
+    fn accepts_custom_health_check_paths() {
+        assert!(check_health_check_path("/health").is_ok());
+        assert!(check_health_check_path("/healthz").is_ok());
+        assert!(check_health_check_path("/_lb/status").is_ok());
+    }
+
+    #[test]
+    fn rejects_health_check_paths_that_cannot_be_routed() {
+        assert!(check_health_check_path("healthz").is_err());
+        assert!(check_health_check_path("/healthz/").is_err());
+        assert!(check_health_check_path("/status/:id").is_err());
+    }
+
+    #[test]
+    fn rejects_health_check_paths_that_conflict_with_routes() {
+        assert!(check_health_check_path("/ready").is_err());
+        assert!(check_health_check_path("/api").is_err());
+        assert!(check_health_check_path("/api/config").is_err());
+        assert!(check_health_check_path("/api/time").is_err());
+    }
+
+    #[test]
+    fn environment_names_and_aliases_parse_case_insensitively() {
+        assert_eq!(Environment::parse("development"), Some(Environment::Development));
+        assert_eq!(Environment::parse("dev"), Some(Environment::Development));
+        assert_eq!(Environment::parse("Staging"), Some(Environment::Staging));
+        assert_eq!(Environment::parse("PROD"), Some(Environment::Production));
+    }
+
+    #[test]
+    fn unknown_or_empty_environment_is_rejected() {
+        assert_eq!(Environment::parse(""), None);
+        assert_eq!(Environment::parse("test"), None);
+    }
+}
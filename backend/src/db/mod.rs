@@ -1,11 +1,165 @@
-use sqlx::{postgres::PgPoolOptions, Pool, Postgres};
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use log::LevelFilter;
+use sqlx::{
+    migrate::Migrator,
+    pool::PoolConnection,
+    postgres::{PgConnectOptions, PgPoolOptions, PgSslMode},
+    ConnectOptions, Pool, Postgres, Transaction,
+};
+use tracing::{info, warn};
+
+use crate::config::Config;
+
+pub mod tx;
 
 pub type DbPool = Pool<Postgres>;
 
-pub async fn create_pool(database_url: &str) -> Result<DbPool, sqlx::Error> {
+// Connections are recycled after `DB_MAX_LIFETIME_SECS`, or once idle for
+// `DB_IDLE_TIMEOUT_SECS`, so PgBouncer/load balancers never hand us a stale one.
+// With `DB_TEST_BEFORE_ACQUIRE`, each connection is pinged before it is handed
+// out, so one the proxy dropped is replaced instead of failing the query.
+//
+// Queries run as named prepared statements (sqlx's query macros always are;
+// a statement cache capacity of 0 only stops caching them, leaving each one
+// open on the server). Behind PgBouncer in transaction mode this requires
+// PgBouncer >= 1.21 with `max_prepared_statements` set.
+pub async fn create_pool(config: &Config) -> Result<DbPool, sqlx::Error> {
+    let mut options: PgConnectOptions = config.database_url.parse()?;
+
+    // sqlx logs every statement at DEBUG by default; only allow it when
+    // DB_LOG_QUERIES is on (never in production). Slow statements are
+    // still reported at WARN.
+    options = if config.db_log_queries {
+        options.log_statements(LevelFilter::Debug)
+    } else {
+        options.log_statements(LevelFilter::Off)
+    };
+
+    if let Some(ssl_mode) = config.db_ssl_mode {
+        options = options.ssl_mode(ssl_mode);
+    }
+    if let Some(root_cert) = &config.db_ssl_root_cert {
+        options = options.ssl_root_cert(root_cert);
+    }
+
+    let ssl_mode = options.get_ssl_mode();
+    info!(sslmode = ?ssl_mode, root_cert = ?config.db_ssl_root_cert, "Database connection encryption");
+    if config.environment.is_production()
+        && matches!(ssl_mode, PgSslMode::Disable | PgSslMode::Allow | PgSslMode::Prefer)
+    {
+        warn!(
+            sslmode = ?ssl_mode,
+            "Database TLS is not enforced in production; set DB_SSLMODE=require or stricter"
+        );
+    }
+
     PgPoolOptions::new()
         .max_connections(20)
         .min_connections(5)
-        .connect(database_url)
+        .max_lifetime(config.db_max_lifetime)
+        .idle_timeout(config.db_idle_timeout)
+        .test_before_acquire(config.db_test_before_acquire)
+        .connect_with(options)
         .await
-}
\ No newline at end of file
+}
+
+// Log the values a call site is about to bind, as tracing fields at DEBUG
+// under sqlx's own `sqlx::query` target, so they sit next to the statement
+// and its timing inside the request's span. sqlx never logs parameters
+// itself. A no-op unless DB_LOG_QUERIES is on (never in production).
+macro_rules! log_query_params {
+    ($config:expr, $($fields:tt)+) => {
+        if $config.db_log_queries {
+            tracing::debug!(target: "sqlx::query", $($fields)+, "Query parameters");
+        }
+    };
+}
+pub(crate) use log_query_params;
+
+// Migrations from backend/migrations, embedded at compile time
+static MIGRATOR: Migrator = sqlx::migrate!();
+
+// Embedded migrations the database hasn't successfully applied, as
+// "<version>_<description>", so a stale or fresh database fails at startup
+// instead of with a 500 on every request that touches a missing table
+pub async fn pending_migrations(pool: &DbPool) -> Result<Vec<String>, sqlx::Error> {
+    let tracked: bool = sqlx::query_scalar("SELECT to_regclass('_sqlx_migrations') IS NOT NULL")
+        .fetch_one(pool)
+        .await?;
+
+    let applied: Vec<i64> = if tracked {
+        sqlx::query_scalar("SELECT version FROM _sqlx_migrations WHERE success")
+            .fetch_all(pool)
+            .await?
+    } else {
+        Vec::new()
+    };
+
+    Ok(MIGRATOR
+        .iter()
+        .filter(|migration| !migration.migration_type.is_down_migration())
+        .filter(|migration| !applied.contains(&migration.version))
+        .map(|migration| format!("{}_{}", migration.version, migration.description))
+        .collect())
+}
+
+// Apply any pending migrations from backend/migrations
+pub async fn run_migrations(pool: &DbPool) -> Result<(), sqlx::migrate::MigrateError> {
+    MIGRATOR.run(pool).await
+}
+
+// Close the pool after the server has stopped, reporting how many
+// connections were closed and whether any were still checked out
+pub async fn close_pool(pool: &DbPool) {
+    let size = pool.size();
+    let busy = (size as usize).saturating_sub(pool.num_idle());
+
+    if busy > 0 {
+        warn!(busy, "Database connections still in use at shutdown");
+    }
+
+    pool.close().await;
+
+    info!(closed = size, busy, "Database pool closed");
+}
+
+// Acquire a connection from the pool, warning when it takes longer than
+// `slow_threshold` so pool exhaustion shows up before requests time out
+pub async fn acquire(
+    pool: &DbPool,
+    slow_threshold: Duration,
+) -> Result<PoolConnection<Postgres>, sqlx::Error> {
+    timed_acquire(pool, slow_threshold, pool.acquire()).await
+}
+
+// Same as `acquire`, but immediately begins a transaction on the connection
+pub async fn begin(
+    pool: &DbPool,
+    slow_threshold: Duration,
+) -> Result<Transaction<'static, Postgres>, sqlx::Error> {
+    timed_acquire(pool, slow_threshold, pool.begin()).await
+}
+
+async fn timed_acquire<T>(
+    pool: &DbPool,
+    slow_threshold: Duration,
+    acquire: impl Future<Output = T>,
+) -> T {
+    let started = Instant::now();
+    let result = acquire.await;
+    let elapsed = started.elapsed();
+
+    if elapsed > slow_threshold {
+        warn!(
+            elapsed_ms = elapsed.as_millis() as u64,
+            threshold_ms = slow_threshold.as_millis() as u64,
+            idle = pool.num_idle(),
+            size = pool.size(),
+            "Slow database connection acquire"
+        );
+    }
+
+    result
+}
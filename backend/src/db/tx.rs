@@ -0,0 +1,86 @@
+use std::sync::Arc;
+
+use axum::{
+    async_trait,
+    extract::{FromRequestParts, Request},
+    http::request::Parts,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use sqlx::{PgConnection, Postgres, Transaction};
+use tokio::sync::{Mutex, OwnedMutexGuard};
+
+use crate::error::AppError;
+use crate::state::AppState;
+
+// Per-request slot holding the transaction opened by the `Tx` extractor
+#[derive(Clone, Default)]
+struct TxSlot(Arc<Mutex<Option<Transaction<'static, Postgres>>>>);
+
+// Request-scoped transaction. Handlers take `Tx` instead of
+// `State<AppState>` to get automatic transaction management: the
+// transaction is committed by `transaction_layer` when the response is
+// 2xx and rolled back otherwise. BEGIN is deferred to the first
+// `Tx::conn` call, so requests rejected before touching the database
+// (bad bodies, failed validation) never take a pooled connection.
+pub struct Tx {
+    guard: OwnedMutexGuard<Option<Transaction<'static, Postgres>>>,
+    state: AppState,
+}
+
+#[async_trait]
+impl FromRequestParts<AppState> for Tx {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, AppError> {
+        let slot = parts.extensions.get::<TxSlot>().cloned().ok_or_else(|| {
+            tracing::error!("Tx extractor used on a route without transaction_layer");
+            AppError::Internal
+        })?;
+
+        // Only one Tx per request; a second extraction would deadlock
+        let guard = slot.0.try_lock_owned().map_err(|_| {
+            tracing::error!("Tx extractor used more than once in a request");
+            AppError::Internal
+        })?;
+
+        Ok(Tx {
+            guard,
+            state: state.clone(),
+        })
+    }
+}
+
+impl Tx {
+    // The transaction's connection, beginning the transaction on first use
+    pub async fn conn(&mut self) -> Result<&mut PgConnection, AppError> {
+        if self.guard.is_none() {
+            *self.guard = Some(self.state.begin().await?);
+        }
+
+        Ok(self.guard.as_deref_mut().expect("transaction was just begun"))
+    }
+}
+
+// Finalize the transaction opened by a `Tx` extractor, if any:
+// commit on a 2xx response, roll back otherwise
+pub async fn transaction_layer(mut req: Request, next: Next) -> Response {
+    let slot = TxSlot::default();
+    req.extensions_mut().insert(slot.clone());
+
+    let response = next.run(req).await;
+
+    let Some(tx) = slot.0.lock().await.take() else {
+        return response;
+    };
+
+    if response.status().is_success() {
+        if let Err(err) = tx.commit().await {
+            return AppError::from(err).into_response();
+        }
+    } else if let Err(err) = tx.rollback().await {
+        tracing::error!("Failed to roll back request transaction: {}", err);
+    }
+
+    response
+}
@@ -0,0 +1,108 @@
+// Message catalog for error codes, keyed by code and language
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Language {
+    #[default]
+    En,
+    Ja,
+}
+
+impl Language {
+    // Pick the most preferred supported language from an Accept-Language
+    // header (e.g. "ja-JP,ja;q=0.9,en;q=0.8"), defaulting to English
+    pub fn from_accept_language(header: &str) -> Self {
+        let mut candidates: Vec<(&str, f32)> = header
+            .split(',')
+            .filter_map(|entry| {
+                let mut parts = entry.trim().split(';');
+                let tag = parts.next()?.trim();
+                let quality = parts
+                    .find_map(|param| param.trim().strip_prefix("q="))
+                    .and_then(|q| q.parse().ok())
+                    .unwrap_or(1.0);
+                (!tag.is_empty() && quality > 0.0).then_some((tag, quality))
+            })
+            .collect();
+
+        // Stable sort keeps header order for equal quality values
+        candidates.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        candidates
+            .into_iter()
+            .find_map(|(tag, _)| {
+                let primary = tag.split('-').next().unwrap_or(tag);
+                match primary.to_ascii_lowercase().as_str() {
+                    "en" => Some(Language::En),
+                    "ja" => Some(Language::Ja),
+                    _ => None,
+                }
+            })
+            .unwrap_or_default()
+    }
+}
+
+pub fn message(code: &str, language: Language) -> &'static str {
+    match (code, language) {
+        ("bad_request", Language::En) => "The request is invalid",
+        ("bad_request", Language::Ja) => "リクエストが不正です",
+        ("not_found", Language::En) => "Resource not found",
+        ("not_found", Language::Ja) => "リソースが見つかりません",
+        ("method_not_allowed", Language::En) => "The method is not allowed for this resource",
+        ("method_not_allowed", Language::Ja) => "このリソースではそのメソッドは使用できません",
+        ("conflict", Language::En) => "The request conflicts with existing data",
+        ("conflict", Language::Ja) => "既存のデータと競合しています",
+        ("unprocessable_entity", Language::En) => "The request could not be processed",
+        ("unprocessable_entity", Language::Ja) => "リクエストを処理できません",
+        ("unsupported_media_type", Language::En) => "Unsupported media type",
+        ("unsupported_media_type", Language::Ja) => "サポートされていないメディアタイプです",
+        ("payload_too_large", Language::En) => "The request body is too large",
+        ("payload_too_large", Language::Ja) => "リクエストの本文が大きすぎます",
+        ("service_unavailable", Language::En) => "The server is busy, please retry later",
+        ("service_unavailable", Language::Ja) => "サーバーが混雑しています。しばらくしてから再試行してください",
+        ("maintenance", Language::En) => "The service is under maintenance; changes are temporarily disabled",
+        ("maintenance", Language::Ja) => "メンテナンス中のため、現在データの変更はできません",
+        (_, Language::Ja) => "サーバー内部エラーが発生しました",
+        _ => "Internal server error",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn picks_highest_quality_supported_language() {
+        assert_eq!(Language::from_accept_language("ja-JP,ja;q=0.9,en;q=0.8"), Language::Ja);
+        assert_eq!(Language::from_accept_language("ja;q=0.5,en;q=0.8"), Language::En);
+    }
+
+    #[test]
+    fn skips_unsupported_languages() {
+        assert_eq!(Language::from_accept_language("fr-CA,fr;q=0.9,ja;q=0.1"), Language::Ja);
+    }
+
+    #[test]
+    fn keeps_header_order_for_equal_quality() {
+        assert_eq!(Language::from_accept_language("ja, en"), Language::Ja);
+        assert_eq!(Language::from_accept_language("en, ja"), Language::En);
+    }
+
+    #[test]
+    fn ignores_q_zero_and_is_case_insensitive() {
+        assert_eq!(Language::from_accept_language("ja;q=0, en"), Language::En);
+        assert_eq!(Language::from_accept_language("JA-jp"), Language::Ja);
+    }
+
+    #[test]
+    fn defaults_to_english() {
+        assert_eq!(Language::from_accept_language(""), Language::En);
+        assert_eq!(Language::from_accept_language("*"), Language::En);
+        assert_eq!(Language::from_accept_language("de;q=oops"), Language::En);
+    }
+
+    #[test]
+    fn unknown_codes_fall_back_to_internal_error_message() {
+        assert_eq!(message("no_such_code", Language::En), "Internal server error");
+        assert_eq!(message("not_found", Language::Ja), "リソースが見つかりません");
+    }
+}
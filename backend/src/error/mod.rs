@@ -0,0 +1,362 @@
+use axum::{
+    extract::rejection::{BytesRejection, JsonRejection, PathRejection, QueryRejection},
+    http::{header, HeaderValue, StatusCode},
+    response::{IntoResponse, Json, Response},
+};
+use serde_json::json;
+
+mod messages;
+
+pub use messages::Language;
+
+// Postgres SQLSTATE codes we map to client errors
+const UNIQUE_VIOLATION: &str = "23505";
+const FOREIGN_KEY_VIOLATION: &str = "23503";
+
+#[derive(Clone, Debug)]
+pub enum AppError {
+    BadRequest(String),
+    NotFound,
+    MethodNotAllowed,
+    Conflict(String),
+    UnprocessableEntity(String),
+    UnsupportedMediaType(String),
+    PayloadTooLarge(String),
+    ServiceUnavailable,
+    // Writes are disabled by MAINTENANCE_MODE
+    Maintenance { retry_after_secs: u64 },
+    Internal,
+}
+
+impl AppError {
+    fn status(&self) -> StatusCode {
+        match self {
+            AppError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            AppError::NotFound => StatusCode::NOT_FOUND,
+            AppError::MethodNotAllowed => StatusCode::METHOD_NOT_ALLOWED,
+            AppError::Conflict(_) => StatusCode::CONFLICT,
+            AppError::UnprocessableEntity(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            AppError::UnsupportedMediaType(_) => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            AppError::PayloadTooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+            AppError::ServiceUnavailable | AppError::Maintenance { .. } => {
+                StatusCode::SERVICE_UNAVAILABLE
+            }
+            AppError::Internal => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    // Stable machine-readable error code
+    fn code(&self) -> &'static str {
+        match self {
+            AppError::BadRequest(_) => "bad_request",
+            AppError::NotFound => "not_found",
+            AppError::MethodNotAllowed => "method_not_allowed",
+            AppError::Conflict(_) => "conflict",
+            AppError::UnprocessableEntity(_) => "unprocessable_entity",
+            AppError::UnsupportedMediaType(_) => "unsupported_media_type",
+            AppError::PayloadTooLarge(_) => "payload_too_large",
+            AppError::ServiceUnavailable => "service_unavailable",
+            AppError::Maintenance { .. } => "maintenance",
+            AppError::Internal => "internal_error",
+        }
+    }
+
+    // Request-specific detail, in English, for variants that carry one
+    fn detail(&self) -> Option<String> {
+        match self {
+            AppError::BadRequest(detail)
+            | AppError::Conflict(detail)
+            | AppError::UnprocessableEntity(detail)
+            | AppError::UnsupportedMediaType(detail)
+            | AppError::PayloadTooLarge(detail) => Some(detail.clone()),
+            AppError::NotFound
+            | AppError::MethodNotAllowed
+            | AppError::ServiceUnavailable
+            | AppError::Maintenance { .. }
+            | AppError::Internal => None,
+        }
+    }
+}
+
+// Attached to error responses so the localization middleware can rebuild
+// the body in the client's language
+#[derive(Clone, Debug)]
+pub struct ErrorInfo {
+    pub code: &'static str,
+    pub detail: Option<String>,
+}
+
+impl ErrorInfo {
+    pub fn body(&self, language: Language) -> serde_json::Value {
+        let mut body = json!({
+            "code": self.code,
+            "message": messages::message(self.code, language),
+        });
+
+        if let Some(detail) = &self.detail {
+            body["detail"] = json!(detail);
+        }
+
+        body
+    }
+}
+
+impl IntoResponse for AppError {
+    fn into_response(self) -> Response {
+        let info = ErrorInfo {
+            code: self.code(),
+            detail: self.detail(),
+        };
+
+        let mut response = (self.status(), Json(info.body(Language::default()))).into_response();
+        response.extensions_mut().insert(info);
+
+        if let AppError::Maintenance { retry_after_secs } = self {
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, HeaderValue::from(retry_after_secs));
+        }
+
+        response
+    }
+}
+
+impl AppError {
+    // Map an axum extractor rejection by its status, keeping axum's
+    // explanation as the detail
+    fn from_rejection(status: StatusCode, detail: String) -> Self {
+        match status {
+            StatusCode::UNSUPPORTED_MEDIA_TYPE => AppError::UnsupportedMediaType(detail),
+            StatusCode::UNPROCESSABLE_ENTITY => AppError::UnprocessableEntity(detail),
+            StatusCode::PAYLOAD_TOO_LARGE => AppError::PayloadTooLarge(detail),
+            status if status.is_client_error() => AppError::BadRequest(detail),
+            _ => {
+                tracing::error!("Extractor rejected request: {}", detail);
+                AppError::Internal
+            }
+        }
+    }
+}
+
+impl From<JsonRejection> for AppError {
+    fn from(rejection: JsonRejection) -> Self {
+        AppError::from_rejection(rejection.status(), rejection.body_text())
+    }
+}
+
+impl From<PathRejection> for AppError {
+    fn from(rejection: PathRejection) -> Self {
+        AppError::from_rejection(rejection.status(), rejection.body_text())
+    }
+}
+
+impl From<QueryRejection> for AppError {
+    fn from(rejection: QueryRejection) -> Self {
+        AppError::from_rejection(rejection.status(), rejection.body_text())
+    }
+}
+
+impl From<BytesRejection> for AppError {
+    fn from(rejection: BytesRejection) -> Self {
+        AppError::from_rejection(rejection.status(), rejection.body_text())
+    }
+}
+
+// Map database errors to responses: missing rows become 404, unique
+// violations 409 and foreign-key violations 422; anything else is a 500
+impl From<sqlx::Error> for AppError {
+    fn from(err: sqlx::Error) -> Self {
+        if let sqlx::Error::RowNotFound = err {
+            return AppError::NotFound;
+        }
+
+        if let Some(db_err) = err.as_database_error() {
+            let constraint = db_err.constraint().unwrap_or("unknown");
+
+            match db_err.code().as_deref() {
+                Some(UNIQUE_VIOLATION) => {
+                    return AppError::Conflict(format!(
+                        "Unique constraint violated: {}",
+                        constraint
+                    ));
+                }
+                Some(FOREIGN_KEY_VIOLATION) => {
+                    return AppError::UnprocessableEntity(format!(
+                        "Foreign key constraint violated: {}",
+                        constraint
+                    ));
+                }
+                _ => {}
+            }
+        }
+
+        tracing::error!("Database error: {}", err);
+        AppError::Internal
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+    use std::error::Error as StdError;
+    use std::fmt;
+
+    use sqlx::error::{DatabaseError, ErrorKind};
+
+    use super::*;
+
+    // Stand-in for a Postgres error carrying a SQLSTATE and constraint name
+    #[derive(Debug)]
+    struct FakeDbError {
+        code: &'static str,
+        constraint: Option<&'static str>,
+    }
+
+    impl fmt::Display for FakeDbError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "SQLSTATE {}", self.code)
+        }
+    }
+
+    impl StdError for FakeDbError {}
+
+    impl DatabaseError for FakeDbError {
+        fn message(&self) -> &str {
+            "fake database error"
+        }
+
+        fn code(&self) -> Option<Cow<'_, str>> {
+            Some(Cow::Borrowed(self.code))
+        }
+
+        fn constraint(&self) -> Option<&str> {
+            self.constraint
+        }
+
+        fn as_error(&self) -> &(dyn StdError + Send + Sync + 'static) {
+            self
+        }
+
+        fn as_error_mut(&mut self) -> &mut (dyn StdError + Send + Sync + 'static) {
+            self
+        }
+
+        fn into_error(self: Box<Self>) -> Box<dyn StdError + Send + Sync + 'static> {
+            self
+        }
+
+        fn kind(&self) -> ErrorKind {
+            ErrorKind::Other
+        }
+    }
+
+    fn from_db_error(code: &'static str, constraint: Option<&'static str>) -> AppError {
+        sqlx::Error::Database(Box::new(FakeDbError { code, constraint })).into()
+    }
+
+    #[test]
+    fn unique_violation_is_a_conflict_naming_the_constraint() {
+        let err = from_db_error(UNIQUE_VIOLATION, Some("test_versions_pkey"));
+
+        assert_eq!(err.status(), StatusCode::CONFLICT);
+        assert_eq!(err.code(), "conflict");
+        assert_eq!(
+            err.detail().as_deref(),
+            Some("Unique constraint violated: test_versions_pkey")
+        );
+    }
+
+    #[test]
+    fn foreign_key_violation_is_unprocessable_naming_the_constraint() {
+        let err = from_db_error(FOREIGN_KEY_VIOLATION, Some("test_versions_test_id_fkey"));
+
+        assert_eq!(err.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(err.code(), "unprocessable_entity");
+        assert_eq!(
+            err.detail().as_deref(),
+            Some("Foreign key constraint violated: test_versions_test_id_fkey")
+        );
+    }
+
+    #[test]
+    fn missing_constraint_name_is_reported_as_unknown() {
+        let err = from_db_error(UNIQUE_VIOLATION, None);
+
+        assert_eq!(err.detail().as_deref(), Some("Unique constraint violated: unknown"));
+    }
+
+    #[test]
+    fn other_database_errors_are_internal_without_detail() {
+        let err = from_db_error("42P01", None);
+
+        assert_eq!(err.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(err.code(), "internal_error");
+        assert_eq!(err.detail(), None);
+    }
+
+    // Against a real database (fresh, with the migrations applied): Postgres
+    // must report 23503 with the constraint name that From<sqlx::Error> reads
+    #[sqlx::test]
+    async fn orphan_version_insert_is_a_422(pool: sqlx::PgPool) {
+        let err = sqlx::query(
+            "INSERT INTO comic.test_versions (test_id, version, title) VALUES ($1, 1, 'Orphan')",
+        )
+        .bind(uuid::Uuid::new_v4())
+        .execute(&pool)
+        .await
+        .unwrap_err();
+
+        let response = AppError::from(err).into_response();
+        let info = response.extensions().get::<ErrorInfo>().unwrap();
+
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(info.code, "unprocessable_entity");
+        assert_eq!(
+            info.detail.as_deref(),
+            Some("Foreign key constraint violated: test_versions_test_id_fkey")
+        );
+    }
+
+    #[test]
+    fn row_not_found_is_404() {
+        let err = AppError::from(sqlx::Error::RowNotFound);
+
+        assert_eq!(err.status(), StatusCode::NOT_FOUND);
+        assert_eq!(err.code(), "not_found");
+    }
+
+    #[test]
+    fn maintenance_is_a_503_with_retry_after() {
+        let response = AppError::Maintenance { retry_after_secs: 120 }.into_response();
+
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(response.headers()[header::RETRY_AFTER], "120");
+        assert_eq!(response.extensions().get::<ErrorInfo>().unwrap().code, "maintenance");
+    }
+
+    #[test]
+    fn rejections_keep_their_client_error_status() {
+        let cases = [
+            (StatusCode::BAD_REQUEST, "bad_request"),
+            (StatusCode::UNPROCESSABLE_ENTITY, "unprocessable_entity"),
+            (StatusCode::UNSUPPORTED_MEDIA_TYPE, "unsupported_media_type"),
+            (StatusCode::PAYLOAD_TOO_LARGE, "payload_too_large"),
+            (StatusCode::METHOD_NOT_ALLOWED, "bad_request"),
+        ];
+
+        for (status, code) in cases {
+            let err = AppError::from_rejection(status, "axum says no".to_string());
+
+            assert_eq!(err.code(), code, "{}", status);
+            assert_eq!(err.detail().as_deref(), Some("axum says no"));
+        }
+    }
+
+    #[test]
+    fn server_side_rejections_are_internal() {
+        let err = AppError::from_rejection(StatusCode::INTERNAL_SERVER_ERROR, "no params".to_string());
+
+        assert_eq!(err.code(), "internal_error");
+        assert_eq!(err.detail(), None);
+    }
+}
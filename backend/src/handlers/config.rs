@@ -0,0 +1,22 @@
+use axum::{extract::State, response::Json};
+
+use crate::handlers::test::{MAX_BATCH_IDS, MAX_SUGGESTIONS};
+use crate::models::{PublicConfig, PublicLimits};
+use crate::state::AppState;
+
+// GET /api/config
+// Client-safe subset of the server configuration; never includes secrets
+pub async fn get_public_config(State(state): State<AppState>) -> Json<PublicConfig> {
+    let config = &state.config;
+
+    Json(PublicConfig {
+        limits: PublicLimits {
+            title_max_bytes: config.field_limits.title_max_bytes,
+            content_max_bytes: config.field_limits.content_max_bytes,
+            max_body_bytes: config.max_body_bytes,
+            max_batch_ids: MAX_BATCH_IDS,
+            max_suggestions: MAX_SUGGESTIONS,
+        },
+        maintenance_mode: config.maintenance_mode,
+    })
+}
@@ -0,0 +1,80 @@
+use std::time::{Duration, Instant};
+
+use axum::{extract::State, http::StatusCode};
+use tokio::sync::RwLock;
+
+use crate::db::DbPool;
+use crate::state::AppState;
+
+// Upper bound on how long a readiness probe waits for the database
+const READINESS_CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+// Caches the last readiness result for a short time so frequent probes
+// don't each run a database query
+pub struct ReadinessCache {
+    ttl: Duration,
+    last_check: RwLock<Option<(Instant, bool)>>,
+}
+
+impl ReadinessCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            last_check: RwLock::new(None),
+        }
+    }
+
+    async fn is_ready(&self, pool: &DbPool) -> bool {
+        if let Some(ready) = self.fresh(&*self.last_check.read().await) {
+            return ready;
+        }
+
+        // Only one probe re-checks; the others wait and reuse its result
+        let mut last_check = self.last_check.write().await;
+        if let Some(ready) = self.fresh(&last_check) {
+            return ready;
+        }
+
+        let ready = check_database(pool).await;
+        *last_check = Some((Instant::now(), ready));
+        ready
+    }
+
+    fn fresh(&self, last_check: &Option<(Instant, bool)>) -> Option<bool> {
+        last_check
+            .filter(|(checked_at, _)| checked_at.elapsed() < self.ttl)
+            .map(|(_, ready)| ready)
+    }
+}
+
+async fn check_database(pool: &DbPool) -> bool {
+    let check = sqlx::query("SELECT 1").execute(pool);
+
+    match tokio::time::timeout(READINESS_CHECK_TIMEOUT, check).await {
+        Ok(Ok(_)) => true,
+        Ok(Err(err)) => {
+            tracing::warn!("Readiness check failed: {}", err);
+            false
+        }
+        Err(_) => {
+            tracing::warn!("Readiness check timed out");
+            false
+        }
+    }
+}
+
+// GET /health
+// Liveness: cheap and uncached, never touches the database
+pub async fn health() -> &'static str {
+    "OK"
+}
+
+// GET /ready
+// Readiness: verifies the database is reachable (result briefly cached)
+pub async fn ready(State(state): State<AppState>) -> (StatusCode, &'static str) {
+    if state.readiness.is_ready(&state.pool).await {
+        (StatusCode::OK, "READY")
+    } else {
+        (StatusCode::SERVICE_UNAVAILABLE, "NOT READY")
+    }
+}
@@ -1 +1,6 @@
-pub mod test;
\ No newline at end of file
+pub mod config;
+pub mod health;
+pub mod render;
+pub mod test;
+pub mod time;
+pub mod version;
\ No newline at end of file
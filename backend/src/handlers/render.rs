@@ -0,0 +1,28 @@
+use axum::extract::State;
+use pulldown_cmark::{html, Options, Parser};
+
+use crate::error::AppError;
+use crate::extract::Json;
+use crate::models::{RenderMarkdownRequest, RenderedMarkdown};
+use crate::state::AppState;
+
+// POST /api/render/markdown
+// Render Markdown to HTML and sanitize it with ammonia's default policy
+// (no scripts, styles or event handlers), so every client shows the same output
+pub async fn render_markdown(
+    State(state): State<AppState>,
+    Json(payload): Json<RenderMarkdownRequest>,
+) -> Result<Json<RenderedMarkdown>, AppError> {
+    payload.validate(&state.config.field_limits)?;
+
+    let parser = Parser::new_ext(
+        &payload.markdown,
+        Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH,
+    );
+    let mut unsafe_html = String::new();
+    html::push_html(&mut unsafe_html, parser);
+
+    Ok(Json(RenderedMarkdown {
+        html: ammonia::clean(&unsafe_html),
+    }))
+}
@@ -1,37 +1,314 @@
+use std::collections::HashMap;
+
 use axum::{
-    extract::{Path, State},
-    http::StatusCode,
-    response::Json,
+    extract::State,
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
 };
-use sqlx::PgPool;
+use chrono::{DateTime, Utc};
+use serde_json::json;
+use sqlx::{postgres::PgRow, PgConnection, Postgres, QueryBuilder, Row};
 use uuid::Uuid;
 
-use crate::models::{CreateTestRequest, Test, UpdateTestRequest};
+use crate::config::Config;
+use crate::db::{log_query_params, tx::Tx};
+use crate::error::AppError;
+use crate::extract::{Json, Path, Query, RawBody};
+use crate::handlers::version::record_version;
+use crate::models::{
+    parse_fields, validate_fields, warn_fields, CreateTestRequest, FieldDescriptor,
+    GetTestQuery, ListTestsQuery, PatchOperation, SuggestTestsQuery, Test, TestCount,
+    TestFilterQuery, TestSchema, TestSuggestion, TestSummary, TestWriteResponse,
+    UpdateTestRequest, UpsertTestRequest,
+};
+use crate::state::AppState;
+
+// Maximum number of ids accepted by GET /api/tests?ids=
+pub const MAX_BATCH_IDS: usize = 100;
 
-// GET /api/tests
+// GET /api/tests (content cut to content_preview)
+// GET /api/tests?ids=uuid1,uuid2,... (same shape, in request order)
+// GET /api/tests?fields=id,title,content_preview
 pub async fn list_tests(
-    State(pool): State<PgPool>,
-) -> Result<Json<Vec<Test>>, StatusCode> {
+    State(state): State<AppState>,
+    Query(query): Query<ListTestsQuery>,
+) -> Result<Response, AppError> {
+    let ids = query.filter.ids.as_deref().map(parse_ids).transpose()?;
+
+    if let Some(fields) = query.fields.as_deref() {
+        let fields = parse_fields(fields, &state.test_fields)?;
+        let tests = fetch_partial_tests(&state, ids.as_deref(), &fields).await?;
+        return Ok(Json(tests).into_response());
+    }
+
+    if let Some(ids) = ids {
+        let tests = get_test_summaries_by_ids(&state, &ids).await?;
+        return Ok(Json(tests).into_response());
+    }
+
+    let mut conn = state.acquire().await?;
+
+    log_query_params!(state.config, content_preview_chars = state.config.content_preview_chars);
     let tests = sqlx::query_as!(
-        Test,
+        TestSummary,
         r#"
-        SELECT id, title, content, created_at, updated_at
+        SELECT id, title, left(content, $1) AS content_preview, created_at, updated_at
         FROM comic.test
         ORDER BY created_at DESC
-        "#
+        "#,
+        state.config.content_preview_chars
     )
-    .fetch_all(&pool)
-    .await
-    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    .fetch_all(&mut *conn)
+    .await?;
 
-    Ok(Json(tests))
+    Ok(Json(tests).into_response())
+}
+
+fn parse_ids(ids: &str) -> Result<Vec<Uuid>, AppError> {
+    let ids = ids
+        .split(',')
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(Uuid::parse_str)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| AppError::BadRequest("ids must be comma-separated UUIDs".to_string()))?;
+
+    if ids.len() > MAX_BATCH_IDS {
+        return Err(AppError::BadRequest(format!(
+            "At most {} ids can be requested at once",
+            MAX_BATCH_IDS
+        )));
+    }
+
+    Ok(ids)
+}
+
+// Fetch several tests in one query, returned in request order with content
+// cut to content_preview like the plain list. Unknown ids are omitted
+// rather than failing the whole request.
+async fn get_test_summaries_by_ids(state: &AppState, ids: &[Uuid]) -> Result<Vec<TestSummary>, AppError> {
+    if ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut conn = state.acquire().await?;
+
+    log_query_params!(
+        state.config,
+        ids = ?ids,
+        content_preview_chars = state.config.content_preview_chars
+    );
+    let tests = sqlx::query_as!(
+        TestSummary,
+        r#"
+        SELECT id, title, left(content, $2) AS content_preview, created_at, updated_at
+        FROM comic.test
+        WHERE id = ANY($1)
+        "#,
+        ids,
+        state.config.content_preview_chars
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    Ok(order_by_ids(ids, tests.into_iter().map(|test| (test.id, test))))
+}
+
+fn order_by_ids<T>(ids: &[Uuid], items: impl Iterator<Item = (Uuid, T)>) -> Vec<T> {
+    let mut by_id: HashMap<Uuid, T> = items.collect();
+
+    ids.iter().filter_map(|id| by_id.remove(id)).collect()
+}
+
+// Sparse fieldsets: select only the requested columns and return partial
+// objects. Column names come from the field descriptors, never from the request.
+async fn fetch_partial_tests(
+    state: &AppState,
+    ids: Option<&[Uuid]>,
+    fields: &[&FieldDescriptor],
+) -> Result<Vec<serde_json::Value>, AppError> {
+    if ids.is_some_and(|ids| ids.is_empty()) {
+        return Ok(Vec::new());
+    }
+
+    // id is always selected so batch results can be put in request order
+    let mut query = QueryBuilder::<Postgres>::new("SELECT id AS _id");
+
+    for field in fields {
+        query.push(", ");
+        match field.name {
+            "content_preview" => {
+                query
+                    .push("left(content, ")
+                    .push_bind(state.config.content_preview_chars)
+                    .push(") AS content_preview");
+            }
+            column => {
+                query.push(column);
+            }
+        }
+    }
+
+    query.push(" FROM comic.test");
+
+    push_filters(&mut query, ids);
+    if ids.is_none() {
+        query.push(" ORDER BY created_at DESC");
+    }
+
+    let mut conn = state.acquire().await?;
+    log_query_params!(
+        state.config,
+        ids = ?ids,
+        content_preview_chars = state.config.content_preview_chars
+    );
+    let rows = query.build().fetch_all(&mut *conn).await?;
+
+    let items = rows
+        .iter()
+        .map(|row| Ok((row.try_get::<Uuid, _>("_id")?, partial_test(row, fields)?)))
+        .collect::<Result<Vec<_>, sqlx::Error>>()?;
+
+    Ok(match ids {
+        Some(ids) => order_by_ids(ids, items.into_iter()),
+        None => items.into_iter().map(|(_, item)| item).collect(),
+    })
+}
+
+// WHERE clause shared by the list and count queries
+fn push_filters<'a>(query: &mut QueryBuilder<'a, Postgres>, ids: Option<&'a [Uuid]>) {
+    if let Some(ids) = ids {
+        query.push(" WHERE id = ANY(").push_bind(ids).push(")");
+    }
+}
+
+// GET /api/tests/count
+// Number of tests matching the same filters as GET /api/tests
+pub async fn count_tests(
+    State(state): State<AppState>,
+    Query(filter): Query<TestFilterQuery>,
+) -> Result<Json<TestCount>, AppError> {
+    let ids = filter.ids.as_deref().map(parse_ids).transpose()?;
+
+    let mut query = QueryBuilder::<Postgres>::new("SELECT COUNT(*) FROM comic.test");
+    push_filters(&mut query, ids.as_deref());
+
+    let mut conn = state.acquire().await?;
+    log_query_params!(state.config, ids = ?ids);
+    let count: i64 = query.build_query_scalar().fetch_one(&mut *conn).await?;
+
+    Ok(Json(TestCount { count }))
+}
+
+fn partial_test(row: &PgRow, fields: &[&FieldDescriptor]) -> Result<serde_json::Value, sqlx::Error> {
+    let mut object = serde_json::Map::new();
+
+    for field in fields {
+        let value = match field.field_type {
+            "uuid" => json!(row.try_get::<Option<Uuid>, _>(field.name)?),
+            "datetime" => json!(row.try_get::<Option<DateTime<Utc>>, _>(field.name)?),
+            _ => json!(row.try_get::<Option<String>, _>(field.name)?),
+        };
+        object.insert(field.name.to_string(), value);
+    }
+
+    Ok(serde_json::Value::Object(object))
+}
+
+// Maximum number of suggestions returned by GET /api/tests/suggest
+pub const MAX_SUGGESTIONS: i64 = 10;
+
+// GET /api/tests/schema
+// Field metadata for admin tooling that builds forms automatically
+pub async fn test_schema(State(state): State<AppState>) -> Json<TestSchema> {
+    Json(TestSchema {
+        entity: "test",
+        fields: state.test_fields.to_vec(),
+    })
+}
+
+// GET /api/tests/suggest?q=
+// Case-insensitive title prefix match, shortest (closest) titles first
+pub async fn suggest_tests(
+    State(state): State<AppState>,
+    Query(query): Query<SuggestTestsQuery>,
+) -> Result<Json<Vec<TestSuggestion>>, AppError> {
+    let prefix = query.q.unwrap_or_default();
+    let prefix = prefix.trim();
+
+    if prefix.is_empty() {
+        return Ok(Json(Vec::new()));
+    }
+
+    // Escape LIKE wildcards so the query is a literal prefix match
+    let pattern = format!(
+        "{}%",
+        prefix
+            .to_lowercase()
+            .replace('\\', "\\\\")
+            .replace('%', "\\%")
+            .replace('_', "\\_")
+    );
+
+    let mut conn = state.acquire().await?;
+
+    log_query_params!(state.config, pattern = ?pattern, limit = MAX_SUGGESTIONS);
+    let suggestions = sqlx::query_as!(
+        TestSuggestion,
+        r#"
+        SELECT id, title
+        FROM comic.test
+        WHERE lower(title) LIKE $1
+        ORDER BY length(title), created_at DESC
+        LIMIT $2
+        "#,
+        pattern,
+        MAX_SUGGESTIONS
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    Ok(Json(suggestions))
 }
 
 // GET /api/tests/:id
+// GET /api/tests/:id?fields=id,title
+// Concurrent requests for the same id share a single query
 pub async fn get_test(
-    State(pool): State<PgPool>,
+    State(state): State<AppState>,
     Path(id): Path<Uuid>,
-) -> Result<Json<Test>, StatusCode> {
+    Query(query): Query<GetTestQuery>,
+) -> Result<Response, AppError> {
+    if let Some(fields) = query.fields.as_deref() {
+        let fields = parse_fields(fields, &state.test_fields)?;
+        let test = fetch_partial_tests(&state, Some(&[id]), &fields)
+            .await?
+            .pop()
+            .ok_or(AppError::NotFound)?;
+        return Ok(Json(test).into_response());
+    }
+
+    let test = state
+        .test_reads
+        .run(id, || fetch_test(&state, id))
+        .await?;
+
+    Ok(Json(test).into_response())
+}
+
+async fn fetch_test(state: &AppState, id: Uuid) -> Result<Test, AppError> {
+    let mut conn = state.acquire().await?;
+
+    select_test(&mut conn, id, &state.config).await
+}
+
+// Current row for a test, on a caller-provided connection or transaction
+pub async fn select_test(
+    conn: &mut PgConnection,
+    id: Uuid,
+    config: &Config,
+) -> Result<Test, AppError> {
+    log_query_params!(config, %id);
     let test = sqlx::query_as!(
         Test,
         r#"
@@ -41,18 +318,24 @@ pub async fn get_test(
         "#,
         id
     )
-    .fetch_one(&pool)
-    .await
-    .map_err(|_| StatusCode::NOT_FOUND)?;
+    .fetch_one(conn)
+    .await?;
 
-    Ok(Json(test))
+    Ok(test)
 }
 
 // POST /api/tests
 pub async fn create_test(
-    State(pool): State<PgPool>,
+    State(state): State<AppState>,
+    mut tx: Tx,
     Json(payload): Json<CreateTestRequest>,
-) -> Result<(StatusCode, Json<Test>), StatusCode> {
+) -> Result<(StatusCode, Json<TestWriteResponse>), AppError> {
+    payload.validate(&state.test_fields)?;
+    let warnings = payload.warnings();
+
+    let conn = tx.conn().await?;
+
+    log_query_params!(state.config, title = ?payload.title, content = ?payload.content);
     let test = sqlx::query_as!(
         Test,
         r#"
@@ -63,47 +346,281 @@ pub async fn create_test(
         payload.title,
         payload.content
     )
-    .fetch_one(&pool)
-    .await
-    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    .fetch_one(&mut *conn)
+    .await?;
 
-    Ok((StatusCode::CREATED, Json(test)))
+    record_version(conn, &test, &state.config).await?;
+
+    Ok((StatusCode::CREATED, Json(TestWriteResponse { test, warnings })))
+}
+
+// PUT /api/tests/by-title/:title
+// Create-if-not-exists by title: 201 with the new test, or 200 with the
+// existing one (left unchanged)
+pub async fn put_test_by_title(
+    State(state): State<AppState>,
+    Path(title): Path<String>,
+    mut tx: Tx,
+    Json(payload): Json<UpsertTestRequest>,
+) -> Result<(StatusCode, Json<TestWriteResponse>), AppError> {
+    validate_fields(
+        Some(&title),
+        payload.content.as_deref(),
+        &state.test_fields,
+    )?;
+    let warnings = warn_fields(Some(&title), payload.content.as_deref());
+
+    let conn = tx.conn().await?;
+
+    // Titles aren't unique, so serialize requests for the same title until
+    // this transaction ends; a concurrent one then sees our insert below.
+    // Keyed on comic.test's oid to stay clear of other advisory lock users.
+    log_query_params!(state.config, ?title);
+    sqlx::query("SELECT pg_advisory_xact_lock('comic.test'::regclass::oid::int, hashtext($1))")
+        .bind(&title)
+        .execute(&mut *conn)
+        .await?;
+
+    // Pre-existing duplicates resolve to the oldest test with the title
+    log_query_params!(state.config, ?title);
+    let existing = sqlx::query_as!(
+        Test,
+        r#"
+        SELECT id, title, content, created_at, updated_at
+        FROM comic.test
+        WHERE title = $1
+        ORDER BY created_at
+        LIMIT 1
+        "#,
+        title
+    )
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    if let Some(test) = existing {
+        return Ok((StatusCode::OK, Json(TestWriteResponse { test, warnings })));
+    }
+
+    log_query_params!(state.config, ?title, content = ?payload.content);
+    let test = sqlx::query_as!(
+        Test,
+        r#"
+        INSERT INTO comic.test (title, content)
+        VALUES ($1, $2)
+        RETURNING id, title, content, created_at, updated_at
+        "#,
+        title,
+        payload.content
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    record_version(conn, &test, &state.config).await?;
+
+    Ok((StatusCode::CREATED, Json(TestWriteResponse { test, warnings })))
 }
 
 // PUT /api/tests/:id
 pub async fn update_test(
-    State(pool): State<PgPool>,
+    State(state): State<AppState>,
     Path(id): Path<Uuid>,
+    mut tx: Tx,
     Json(payload): Json<UpdateTestRequest>,
-) -> Result<Json<Test>, StatusCode> {
-    // Update updated_at timestamp
-    let test = sqlx::query_as!(
+) -> Result<Json<TestWriteResponse>, AppError> {
+    payload.validate(&state.test_fields)?;
+    let warnings = payload.warnings();
+
+    let conn = tx.conn().await?;
+
+    // updated_at is maintained by the comic.set_updated_at trigger. Rows the
+    // update wouldn't change are left alone so no-op writes don't bump
+    // updated_at or record a duplicate version.
+    log_query_params!(state.config, %id, title = ?payload.title, content = ?payload.content);
+    let updated = sqlx::query_as!(
         Test,
         r#"
         UPDATE comic.test
         SET
             title = COALESCE($2, title),
-            content = COALESCE($3, content),
-            updated_at = CURRENT_TIMESTAMP
+            content = COALESCE($3, content)
         WHERE id = $1
+          AND (title, content) IS DISTINCT FROM (COALESCE($2, title), COALESCE($3, content))
         RETURNING id, title, content, created_at, updated_at
         "#,
         id,
         payload.title,
         payload.content
     )
-    .fetch_one(&pool)
-    .await
-    .map_err(|_| StatusCode::NOT_FOUND)?;
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    let test = match updated {
+        Some(test) => {
+            record_version(conn, &test, &state.config).await?;
+            test
+        }
+        None => select_test(conn, id, &state.config).await?,
+    };
+
+    Ok(Json(TestWriteResponse { test, warnings }))
+}
+
+// Content type required by PATCH /api/tests/:id
+const JSON_PATCH_CONTENT_TYPE: &str = "application/json-patch+json";
+
+// PATCH /api/tests/:id
+// Applies an RFC 6902 JSON Patch limited to add/replace/remove on /title and /content
+pub async fn patch_test(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    headers: HeaderMap,
+    mut tx: Tx,
+    RawBody(body): RawBody,
+) -> Result<Json<TestWriteResponse>, AppError> {
+    let content_type = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+
+    if !content_type.starts_with(JSON_PATCH_CONTENT_TYPE) {
+        return Err(AppError::UnsupportedMediaType(format!(
+            "Expected Content-Type: {}",
+            JSON_PATCH_CONTENT_TYPE
+        )));
+    }
+
+    let operations: Vec<PatchOperation> = serde_json::from_slice(&body)
+        .map_err(|err| AppError::BadRequest(format!("Invalid JSON Patch document: {}", err)))?;
+
+    let conn = tx.conn().await?;
+
+    log_query_params!(state.config, %id);
+    let mut test = sqlx::query_as!(
+        Test,
+        r#"
+        SELECT id, title, content, created_at, updated_at
+        FROM comic.test
+        WHERE id = $1
+        FOR UPDATE
+        "#,
+        id
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    let original = (test.title.clone(), test.content.clone());
+
+    for operation in &operations {
+        apply_patch_operation(&mut test, operation, &state.test_fields)?;
+    }
+
+    validate_fields(
+        Some(&test.title),
+        test.content.as_deref(),
+        &state.test_fields,
+    )?;
+    let warnings = warn_fields(Some(&test.title), test.content.as_deref());
+
+    // Nothing changed: skip the write so updated_at and the history stay put
+    if (&test.title, &test.content) == (&original.0, &original.1) {
+        return Ok(Json(TestWriteResponse { test, warnings }));
+    }
+
+    log_query_params!(state.config, %id, title = ?test.title, content = ?test.content);
+    let test = sqlx::query_as!(
+        Test,
+        r#"
+        UPDATE comic.test
+        SET
+            title = $2,
+            content = $3
+        WHERE id = $1
+        RETURNING id, title, content, created_at, updated_at
+        "#,
+        id,
+        test.title,
+        test.content
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    record_version(conn, &test, &state.config).await?;
 
-    Ok(Json(test))
+    Ok(Json(TestWriteResponse { test, warnings }))
+}
+
+// Writable paths, nullability and types come from the field descriptors
+// served by GET /api/tests/schema
+fn apply_patch_operation(
+    test: &mut Test,
+    operation: &PatchOperation,
+    fields: &[FieldDescriptor],
+) -> Result<(), AppError> {
+    let field = operation
+        .path
+        .strip_prefix('/')
+        .and_then(|name| {
+            fields
+                .iter()
+                .find(|field| field.name == name && !field.read_only)
+        })
+        .ok_or_else(|| {
+            AppError::UnprocessableEntity(format!(
+                "Unsupported patch path: {}",
+                operation.path
+            ))
+        })?;
+
+    let value = match operation.op.as_str() {
+        "add" | "replace" => operation.value.clone().ok_or_else(|| {
+            AppError::UnprocessableEntity(format!(
+                "Operation \"{}\" on {} requires a value",
+                operation.op, operation.path
+            ))
+        })?,
+        "remove" => serde_json::Value::Null,
+        op => {
+            return Err(AppError::UnprocessableEntity(format!(
+                "Unsupported patch operation: {}",
+                op
+            )));
+        }
+    };
+
+    let value = match value {
+        serde_json::Value::String(value) => Some(value),
+        serde_json::Value::Null if field.nullable => None,
+        serde_json::Value::Null => {
+            return Err(AppError::UnprocessableEntity(format!(
+                "{} is required and cannot be removed or null",
+                operation.path
+            )));
+        }
+        _ => {
+            return Err(AppError::UnprocessableEntity(format!(
+                "{} must be a {}",
+                operation.path, field.field_type
+            )));
+        }
+    };
+
+    match (field.name, value) {
+        ("title", Some(title)) => test.title = title,
+        ("content", content) => test.content = content,
+        (name, _) => unreachable!("writable field {} is not patchable", name),
+    }
+
+    Ok(())
 }
 
 // DELETE /api/tests/:id
 pub async fn delete_test(
-    State(pool): State<PgPool>,
+    State(state): State<AppState>,
     Path(id): Path<Uuid>,
-) -> Result<StatusCode, StatusCode> {
+) -> Result<StatusCode, AppError> {
+    let mut conn = state.acquire().await?;
+
+    log_query_params!(state.config, %id);
     let result = sqlx::query!(
         r#"
         DELETE FROM comic.test
@@ -111,13 +628,104 @@ pub async fn delete_test(
         "#,
         id
     )
-    .execute(&pool)
-    .await
-    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    .execute(&mut *conn)
+    .await?;
 
     if result.rows_affected() == 0 {
-        return Err(StatusCode::NOT_FOUND);
+        return Err(AppError::NotFound);
     }
 
     Ok(StatusCode::NO_CONTENT)
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::config::FieldLimits;
+    use crate::models::test_fields;
+
+    fn test() -> Test {
+        Test {
+            id: Uuid::nil(),
+            title: "Title".to_string(),
+            content: Some("Content".to_string()),
+            created_at: None,
+            updated_at: None,
+        }
+    }
+
+    fn apply(operation: serde_json::Value) -> Result<Test, AppError> {
+        let mut test = test();
+        let operation: PatchOperation = serde_json::from_value(operation).unwrap();
+        let fields = test_fields(&FieldLimits {
+            title_max_bytes: 256,
+            content_max_bytes: 1024,
+        });
+        apply_patch_operation(&mut test, &operation, &fields).map(|()| test)
+    }
+
+    fn detail(result: Result<Test, AppError>) -> String {
+        match result {
+            Err(AppError::UnprocessableEntity(detail)) => detail,
+            other => panic!("expected 422, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn replace_and_add_set_the_field() {
+        let patched = apply(json!({"op": "replace", "path": "/title", "value": "New"})).unwrap();
+        assert_eq!(patched.title, "New");
+
+        let patched = apply(json!({"op": "add", "path": "/content", "value": "More"})).unwrap();
+        assert_eq!(patched.content.as_deref(), Some("More"));
+    }
+
+    #[test]
+    fn remove_clears_nullable_fields_only() {
+        let patched = apply(json!({"op": "remove", "path": "/content"})).unwrap();
+        assert_eq!(patched.content, None);
+
+        assert_eq!(
+            detail(apply(json!({"op": "remove", "path": "/title"}))),
+            "/title is required and cannot be removed or null"
+        );
+    }
+
+    #[test]
+    fn rejects_read_only_and_unknown_paths() {
+        assert_eq!(
+            detail(apply(json!({"op": "replace", "path": "/id", "value": "x"}))),
+            "Unsupported patch path: /id"
+        );
+        assert_eq!(
+            detail(apply(json!({"op": "replace", "path": "/content_preview", "value": "x"}))),
+            "Unsupported patch path: /content_preview"
+        );
+        assert_eq!(
+            detail(apply(json!({"op": "replace", "path": "title", "value": "x"}))),
+            "Unsupported patch path: title"
+        );
+    }
+
+    #[test]
+    fn rejects_unsupported_operations_and_missing_values() {
+        assert_eq!(
+            detail(apply(json!({"op": "move", "path": "/title", "from": "/content"}))),
+            "Unsupported patch operation: move"
+        );
+        assert_eq!(
+            detail(apply(json!({"op": "replace", "path": "/title"}))),
+            "Operation \"replace\" on /title requires a value"
+        );
+    }
+
+    #[test]
+    fn rejects_values_of_the_wrong_type() {
+        assert_eq!(
+            detail(apply(json!({"op": "replace", "path": "/content", "value": 42}))),
+            "/content must be a string"
+        );
+    }
+}
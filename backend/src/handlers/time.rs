@@ -0,0 +1,22 @@
+use axum::{
+    http::header,
+    response::{IntoResponse, Json},
+};
+use chrono::{Local, SecondsFormat, Utc};
+
+use crate::models::ServerTime;
+
+// GET /api/time
+// Current server time for clients computing clock skew; never cached
+pub async fn server_time() -> impl IntoResponse {
+    let now = Utc::now();
+
+    (
+        [(header::CACHE_CONTROL, "no-store")],
+        Json(ServerTime {
+            now: now.to_rfc3339_opts(SecondsFormat::Millis, true),
+            unix_ms: now.timestamp_millis(),
+            timezone: Local::now().offset().to_string(),
+        }),
+    )
+}
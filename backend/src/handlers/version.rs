@@ -0,0 +1,197 @@
+use axum::extract::State;
+use sqlx::PgConnection;
+use uuid::Uuid;
+
+use crate::config::Config;
+use crate::db::{log_query_params, tx::Tx};
+use crate::error::AppError;
+use crate::extract::{Json, Path};
+use crate::handlers::test::select_test;
+use crate::models::{
+    validate_fields, warn_fields, FieldChange, Test, TestVersion, TestVersionDiff, TestWriteResponse,
+};
+use crate::state::AppState;
+
+// Append the test's current fields as its next version. Must run in the
+// same transaction as the write: the row lock taken by the write keeps
+// concurrent writers from computing the same version number.
+pub async fn record_version(
+    conn: &mut PgConnection,
+    test: &Test,
+    config: &Config,
+) -> Result<(), AppError> {
+    log_query_params!(config, test_id = %test.id, title = ?test.title, content = ?test.content);
+    sqlx::query!(
+        r#"
+        INSERT INTO comic.test_versions (test_id, version, title, content)
+        SELECT $1, COALESCE(MAX(version), 0) + 1, $2, $3
+        FROM comic.test_versions
+        WHERE test_id = $1
+        "#,
+        test.id,
+        test.title,
+        test.content
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+// GET /api/tests/:id/versions
+// Newest first
+pub async fn list_test_versions(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Vec<TestVersion>>, AppError> {
+    let mut conn = state.acquire().await?;
+
+    log_query_params!(state.config, %id);
+    let versions = sqlx::query_as!(
+        TestVersion,
+        r#"
+        SELECT version, title, content, created_at
+        FROM comic.test_versions
+        WHERE test_id = $1
+        ORDER BY version DESC
+        "#,
+        id
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    // Every test has at least one version, so none means no such test
+    if versions.is_empty() {
+        return Err(AppError::NotFound);
+    }
+
+    Ok(Json(versions))
+}
+
+// GET /api/tests/:id/versions/:version/diff
+// Field-level changes between a version and the one before it
+pub async fn diff_test_version(
+    State(state): State<AppState>,
+    Path((id, version)): Path<(Uuid, i32)>,
+) -> Result<Json<TestVersionDiff>, AppError> {
+    let mut conn = state.acquire().await?;
+
+    log_query_params!(state.config, %id, version);
+    let mut versions = sqlx::query_as!(
+        TestVersion,
+        r#"
+        SELECT version, title, content, created_at
+        FROM comic.test_versions
+        WHERE test_id = $1 AND version <= $2
+        ORDER BY version DESC
+        LIMIT 2
+        "#,
+        id,
+        version
+    )
+    .fetch_all(&mut *conn)
+    .await?
+    .into_iter();
+
+    let current = versions
+        .next()
+        .filter(|current| current.version == version)
+        .ok_or(AppError::NotFound)?;
+    let previous = versions.next();
+
+    let (previous_version, from_title, from_content) = match previous {
+        Some(previous) => (Some(previous.version), Some(previous.title), previous.content),
+        None => (None, None, None),
+    };
+
+    let mut changes = Vec::new();
+
+    if from_title.as_deref() != Some(current.title.as_str()) {
+        changes.push(FieldChange {
+            field: "title",
+            from: from_title,
+            to: Some(current.title),
+        });
+    }
+
+    if from_content != current.content {
+        changes.push(FieldChange {
+            field: "content",
+            from: from_content,
+            to: current.content,
+        });
+    }
+
+    Ok(Json(TestVersionDiff {
+        version,
+        previous_version,
+        changes,
+    }))
+}
+
+// POST /api/tests/:id/versions/:version/restore
+// Copy a version's fields back onto the test, recorded as a new version.
+// Restoring a version that matches the current state changes nothing.
+pub async fn restore_test_version(
+    State(state): State<AppState>,
+    Path((id, version)): Path<(Uuid, i32)>,
+    mut tx: Tx,
+) -> Result<Json<TestWriteResponse>, AppError> {
+    let conn = tx.conn().await?;
+
+    log_query_params!(state.config, %id, version);
+    let restored = sqlx::query!(
+        r#"
+        SELECT title, content
+        FROM comic.test_versions
+        WHERE test_id = $1 AND version = $2
+        "#,
+        id,
+        version
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    // The snapshot passed the limits in force when it was saved; apply the
+    // current ones like any other write
+    validate_fields(
+        Some(&restored.title),
+        restored.content.as_deref(),
+        &state.test_fields,
+    )?;
+    let warnings = warn_fields(Some(&restored.title), restored.content.as_deref());
+
+    // updated_at is maintained by the comic.set_updated_at trigger
+    log_query_params!(
+        state.config,
+        %id,
+        title = ?restored.title,
+        content = ?restored.content
+    );
+    let updated = sqlx::query_as!(
+        Test,
+        r#"
+        UPDATE comic.test
+        SET
+            title = $2,
+            content = $3
+        WHERE id = $1 AND (title, content) IS DISTINCT FROM ($2, $3)
+        RETURNING id, title, content, created_at, updated_at
+        "#,
+        id,
+        restored.title,
+        restored.content
+    )
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    let test = match updated {
+        Some(test) => {
+            record_version(conn, &test, &state.config).await?;
+            test
+        }
+        None => select_test(conn, id, &state.config).await?,
+    };
+
+    Ok(Json(TestWriteResponse { test, warnings }))
+}
@@ -1,26 +1,52 @@
 mod config;
 mod db;
+mod error;
+mod extract;
 mod handlers;
+mod middleware;
 mod models;
+mod singleflight;
+mod state;
+
+use std::time::Duration;
 
 use axum::{
+    extract::{DefaultBodyLimit, Request},
+    middleware::{from_fn, from_fn_with_state},
     routing::get,
-    Router,
+    Router, ServiceExt,
 };
+use axum_server::tls_rustls::RustlsConfig;
+use tower::Layer;
 use tower_http::cors::{Any, CorsLayer};
+use tower_http::normalize_path::NormalizePathLayer;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 use config::Config;
-use db::create_pool;
-use handlers::test::{create_test, delete_test, get_test, list_tests, update_test};
+use db::{close_pool, create_pool, pending_migrations, run_migrations, tx::transaction_layer};
+use error::AppError;
+use handlers::config::get_public_config;
+use handlers::health::{health, ready};
+use handlers::render::render_markdown;
+use handlers::test::{
+    count_tests, create_test, delete_test, get_test, list_tests, patch_test, put_test_by_title, suggest_tests,
+    test_schema, update_test,
+};
+use handlers::time::server_time;
+use handlers::version::{diff_test_version, list_test_versions, restore_test_version};
+use middleware::concurrency::{limit_concurrency, ConcurrencyLimit};
+use middleware::locale::localize_errors;
+use middleware::maintenance::reject_writes_during_maintenance;
+use middleware::pretty::pretty_print_json;
+use middleware::request_id::request_id;
+use state::AppState;
 
-#[tokio::main]
-async fn main() {
+fn main() {
     // Initialize tracing
     tracing_subscriber::registry()
         .with(
             tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| "api=debug,tower_http=debug".into()),
+                .unwrap_or_else(|_| "api=debug,tower_http=debug,sqlx::query=debug".into()),
         )
         .with(tracing_subscriber::fmt::layer())
         .init();
@@ -29,13 +55,61 @@ async fn main() {
     let config = Config::from_env();
     tracing::info!("Starting server with config: {:?}", config);
 
+    // Build the Tokio runtime with the configured number of worker threads
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(config.worker_threads)
+        .enable_all()
+        .build()
+        .expect("Failed to build Tokio runtime");
+
+    tracing::info!("Tokio runtime started with {} worker threads", config.worker_threads);
+
+    runtime.block_on(run(config));
+}
+
+async fn run(config: Config) {
     // Create database connection pool
-    let pool = create_pool(&config.database_url)
+    let pool = create_pool(&config)
         .await
         .expect("Failed to create database pool");
 
     tracing::info!("Database connection established");
 
+    // Apply pending migrations, or refuse to start if any are missing
+    if config.auto_migrate {
+        run_migrations(&pool)
+            .await
+            .expect("Failed to run database migrations");
+        tracing::info!("Database migrations applied");
+    } else {
+        let pending = pending_migrations(&pool)
+            .await
+            .expect("Failed to check applied database migrations");
+
+        if !pending.is_empty() {
+            tracing::error!(
+                ?pending,
+                "Database is missing migrations; run migrations (sqlx migrate run) or set AUTO_MIGRATE=true"
+            );
+            std::process::exit(1);
+        }
+    }
+
+    let state = AppState::new(pool, config);
+
+    if state.config.maintenance_mode {
+        tracing::warn!("Maintenance mode enabled: rejecting writes");
+    }
+
+    // Shared concurrency limit for expensive endpoints
+    let heavy_limit = ConcurrencyLimit::new(
+        state.config.heavy_endpoint_concurrency,
+        state.config.heavy_endpoint_queue_timeout,
+    );
+
+    // Process-wide cap on in-flight requests; excess load gets an immediate 503
+    let global_limit = ConcurrencyLimit::new(state.config.max_concurrent_requests, Duration::ZERO);
+
     // Configure CORS
     let cors = CorsLayer::new()
         .allow_origin(Any)
@@ -43,38 +117,157 @@ async fn main() {
         .allow_headers(Any);
 
     // Build application with routes
-    let app = Router::new()
-        // Health check
-        .route("/health", get(health))
+    let mut app = Router::new()
+        // Client-visible server limits
+        .route("/api/config", get(get_public_config))
+        // Server clock for client skew calculation
+        .route("/api/time", get(server_time))
+        // Server-side Markdown rendering
+        .route("/api/render/markdown", axum::routing::post(render_markdown))
         // Test CRUD endpoints
         .route("/api/tests", axum::routing::get(list_tests).post(create_test))
+        .route("/api/tests/count", get(count_tests))
+        .route("/api/tests/schema", get(test_schema))
+        .route("/api/tests/by-title/:title", axum::routing::put(put_test_by_title))
+        .route(
+            "/api/tests/suggest",
+            get(suggest_tests)
+                .route_layer(from_fn_with_state(heavy_limit, limit_concurrency)),
+        )
         .route(
             "/api/tests/:id",
             axum::routing::get(get_test)
                 .put(update_test)
+                .patch(patch_test)
                 .delete(delete_test),
         )
-        // Add database pool to state
-        .with_state(pool)
-        .layer(cors);
+        // Edit history
+        .route("/api/tests/:id/versions", get(list_test_versions))
+        .route("/api/tests/:id/versions/:version/diff", get(diff_test_version))
+        .route(
+            "/api/tests/:id/versions/:version/restore",
+            axum::routing::post(restore_test_version),
+        )
+        // Applies to the routes above only, so probes keep answering under load
+        .layer(from_fn_with_state(global_limit, limit_concurrency))
+        // Health check
+        .route("/health", get(health))
+        // Readiness check (database reachable)
+        .route("/ready", get(ready));
 
-    // Create listener
-    let addr = format!("{}:{}", config.server_host, config.server_port);
-    let listener = tokio::net::TcpListener::bind(&addr)
-        .await
-        .expect("Failed to bind to address");
+    // Also serve the health check where the load balancer expects it
+    if state.config.health_check_path != "/health" {
+        app = app.route(&state.config.health_check_path, get(health));
+    }
 
-    tracing::info!("🚀 Server running on http://{}", addr);
-    tracing::info!("📍 Health check: http://{}:{}/health", config.server_host, config.server_port);
-    tracing::info!("📍 Test API: http://{}:{}/api/tests", config.server_host, config.server_port);
+    let app = app
+        // Unknown routes and methods get the same JSON error body as
+        // everything else (axum still sets Allow on the 405)
+        .fallback(|| async { AppError::NotFound })
+        .method_not_allowed_fallback(|| async { AppError::MethodNotAllowed })
+        // Cap request bodies read by the Json/RawBody extractors
+        .layer(DefaultBodyLimit::max(state.config.max_body_bytes))
+        // Commit or roll back transactions opened by the Tx extractor
+        .layer(from_fn(transaction_layer))
+        // Reject writes with 503 while in maintenance mode
+        .layer(from_fn_with_state(
+            state.clone(),
+            reject_writes_during_maintenance,
+        ))
+        // Add shared state (database pool and config)
+        .with_state(state.clone())
+        // Localize error messages based on Accept-Language
+        .layer(from_fn(localize_errors))
+        // Pretty-print JSON with ?pretty=true outside production
+        .layer(from_fn_with_state(state.clone(), pretty_print_json))
+        .layer(cors)
+        // Tag all logs for a request with its X-Request-Id
+        .layer(from_fn(request_id));
 
-    // Run server
-    axum::serve(listener, app)
-        .await
-        .expect("Failed to start server");
+    // Strip trailing slashes before routing so /api/tests/ is served as
+    // /api/tests (no redirect). This has to wrap the Router itself, since
+    // layers added with Router::layer run after the route is matched.
+    let app = NormalizePathLayer::trim_trailing_slash().layer(app);
+
+    let addr = format!("{}:{}", state.config.server_host, state.config.server_port);
+
+    // Serve HTTPS directly when a certificate is configured
+    if let Some(tls) = &state.config.tls {
+        let rustls_config = RustlsConfig::from_pem_file(&tls.cert_path, &tls.key_path)
+            .await
+            .expect("Failed to load TLS certificate or key");
+
+        // Bind with tokio so SERVER_HOST can be a hostname as well as an IP
+        let listener = tokio::net::TcpListener::bind(&addr)
+            .await
+            .expect("Failed to bind to address")
+            .into_std()
+            .expect("Failed to convert listener");
+
+        tracing::info!("🔒 TLS enabled with certificate {}", tls.cert_path);
+        tracing::info!("🚀 Server running on https://{}", addr);
+        tracing::info!("📍 Health check: https://{}:{}{}", state.config.server_host, state.config.server_port, state.config.health_check_path);
+        tracing::info!("📍 Test API: https://{}:{}/api/tests", state.config.server_host, state.config.server_port);
+
+        // Stop accepting connections on shutdown and let in-flight requests finish
+        let handle = axum_server::Handle::new();
+        tokio::spawn({
+            let handle = handle.clone();
+            async move {
+                shutdown_signal().await;
+                handle.graceful_shutdown(None);
+            }
+        });
+
+        axum_server::from_tcp_rustls(listener, rustls_config)
+            .handle(handle)
+            .serve(ServiceExt::<Request>::into_make_service(app))
+            .await
+            .expect("Failed to start server");
+    } else {
+        // Create listener
+        let listener = tokio::net::TcpListener::bind(&addr)
+            .await
+            .expect("Failed to bind to address");
+
+        tracing::info!("🚀 Server running on http://{}", addr);
+        tracing::info!("📍 Health check: http://{}:{}{}", state.config.server_host, state.config.server_port, state.config.health_check_path);
+        tracing::info!("📍 Test API: http://{}:{}/api/tests", state.config.server_host, state.config.server_port);
+
+        // Run server
+        axum::serve(listener, ServiceExt::<Request>::into_make_service(app))
+            .with_graceful_shutdown(shutdown_signal())
+            .await
+            .expect("Failed to start server");
+    }
+
+    tracing::info!("Server stopped, draining database pool");
+    close_pool(&state.pool).await;
 }
 
-// Health check endpoint
-async fn health() -> &'static str {
-    "OK"
-}
\ No newline at end of file
+// Resolves on Ctrl+C or SIGTERM
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("Failed to install Ctrl+C handler");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("Failed to install SIGTERM handler")
+            .recv()
+            .await;
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+
+    tracing::info!("Shutdown signal received, finishing in-flight requests");
+}
@@ -0,0 +1,118 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use tokio::sync::Semaphore;
+
+use crate::error::AppError;
+
+// Bounds how many requests run concurrently on the routes it is applied to.
+// Unlike tower's ConcurrencyLimitLayer, waiting for a slot is bounded:
+// requests queue for at most `queue_timeout` and then get a 503. With a
+// zero timeout, requests are only admitted if a slot is free right away.
+#[derive(Clone, Debug)]
+pub struct ConcurrencyLimit {
+    semaphore: Arc<Semaphore>,
+    queue_timeout: Duration,
+    rejections: Arc<RejectionLog>,
+}
+
+impl ConcurrencyLimit {
+    pub fn new(limit: usize, queue_timeout: Duration) -> Self {
+        Self {
+            semaphore: Arc::new(Semaphore::new(limit)),
+            queue_timeout,
+            rejections: Arc::new(RejectionLog::new(REJECTION_LOG_INTERVAL)),
+        }
+    }
+}
+
+// Minimum time between "limit reached" warnings for one limit
+const REJECTION_LOG_INTERVAL: Duration = Duration::from_secs(10);
+
+// Counts rejections so a sustained overload logs one warning per interval
+// (with the number of requests rejected since the last one) instead of one
+// per request
+#[derive(Debug)]
+struct RejectionLog {
+    interval: Duration,
+    count: AtomicU64,
+    last_logged: Mutex<Option<Instant>>,
+}
+
+impl RejectionLog {
+    fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            count: AtomicU64::new(0),
+            last_logged: Mutex::new(None),
+        }
+    }
+
+    // Record a rejection; returns the number to report if a warning is due
+    fn record(&self) -> Option<u64> {
+        self.count.fetch_add(1, Ordering::Relaxed);
+
+        let mut last_logged = self.last_logged.lock().unwrap_or_else(|err| err.into_inner());
+        let now = Instant::now();
+
+        if last_logged.is_some_and(|at| now.duration_since(at) < self.interval) {
+            return None;
+        }
+
+        *last_logged = Some(now);
+        Some(self.count.swap(0, Ordering::Relaxed))
+    }
+}
+
+pub async fn limit_concurrency(
+    State(limit): State<ConcurrencyLimit>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let acquire = limit.semaphore.clone().acquire_owned();
+
+    let Ok(Ok(_permit)) = tokio::time::timeout(limit.queue_timeout, acquire).await else {
+        if let Some(rejected) = limit.rejections.record() {
+            tracing::warn!(
+                path = %req.uri().path(),
+                rejected,
+                "Concurrency limit reached, rejecting requests"
+            );
+        }
+        return AppError::ServiceUnavailable.into_response();
+    };
+
+    next.run(req).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_rejection_is_logged_then_suppressed_within_interval() {
+        let log = RejectionLog::new(Duration::from_secs(60));
+
+        assert_eq!(log.record(), Some(1));
+        assert_eq!(log.record(), None);
+        assert_eq!(log.record(), None);
+    }
+
+    #[test]
+    fn suppressed_rejections_are_counted_in_the_next_warning() {
+        let log = RejectionLog::new(Duration::from_millis(20));
+
+        assert_eq!(log.record(), Some(1));
+        assert_eq!(log.record(), None);
+        assert_eq!(log.record(), None);
+
+        std::thread::sleep(Duration::from_millis(30));
+        assert_eq!(log.record(), Some(3));
+    }
+}
@@ -0,0 +1,34 @@
+use axum::{
+    extract::Request,
+    http::header,
+    middleware::Next,
+    response::{IntoResponse, Json, Response},
+};
+
+use crate::error::{ErrorInfo, Language};
+
+// Rebuild AppError bodies using the message catalog for the language
+// requested via Accept-Language. English responses pass through as-is.
+pub async fn localize_errors(req: Request, next: Next) -> Response {
+    let language = req
+        .headers()
+        .get(header::ACCEPT_LANGUAGE)
+        .and_then(|value| value.to_str().ok())
+        .map(Language::from_accept_language)
+        .unwrap_or_default();
+
+    let response = next.run(req).await;
+
+    if language == Language::default() {
+        return response;
+    }
+
+    match response.extensions().get::<ErrorInfo>().cloned() {
+        Some(info) => {
+            let (mut parts, _) = response.into_parts();
+            parts.headers.remove(header::CONTENT_LENGTH);
+            (parts, Json(info.body(language))).into_response()
+        }
+        None => response,
+    }
+}
@@ -0,0 +1,35 @@
+use axum::{
+    extract::{Request, State},
+    http::Method,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::error::AppError;
+use crate::state::AppState;
+
+// POST routes that don't write anything, so they keep working in maintenance mode
+const READ_ONLY_POSTS: &[&str] = &["/api/render/markdown"];
+
+// While MAINTENANCE_MODE is set, return 503 with Retry-After for writes.
+// Reads (and CORS preflights) are always let through.
+pub async fn reject_writes_during_maintenance(
+    State(state): State<AppState>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let is_read = match *req.method() {
+        Method::GET | Method::HEAD | Method::OPTIONS => true,
+        Method::POST => READ_ONLY_POSTS.contains(&req.uri().path()),
+        _ => false,
+    };
+
+    if state.config.maintenance_mode && !is_read {
+        return AppError::Maintenance {
+            retry_after_secs: state.config.maintenance_retry_after_secs,
+        }
+        .into_response();
+    }
+
+    next.run(req).await
+}
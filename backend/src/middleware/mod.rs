@@ -0,0 +1,5 @@
+pub mod concurrency;
+pub mod locale;
+pub mod maintenance;
+pub mod pretty;
+pub mod request_id;
@@ -0,0 +1,48 @@
+use std::collections::HashMap;
+
+use axum::{
+    body::{to_bytes, Body},
+    extract::{Query, Request, State},
+    http::header,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::error::AppError;
+use crate::state::AppState;
+
+// Pretty-print JSON responses when `?pretty=true` is passed, for reading
+// responses in curl. Always a no-op in production.
+pub async fn pretty_print_json(State(state): State<AppState>, req: Request, next: Next) -> Response {
+    let wants_pretty = !state.config.environment.is_production()
+        && Query::<HashMap<String, String>>::try_from_uri(req.uri())
+            .is_ok_and(|Query(params)| params.get("pretty").is_some_and(|value| value == "true"));
+
+    let response = next.run(req).await;
+
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|content_type| content_type.starts_with("application/json"));
+
+    if !wants_pretty || !is_json {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let Ok(bytes) = to_bytes(body, usize::MAX).await else {
+        return AppError::Internal.into_response();
+    };
+
+    let pretty = serde_json::from_slice::<serde_json::Value>(&bytes)
+        .and_then(|value| serde_json::to_vec_pretty(&value));
+
+    match pretty {
+        Ok(pretty) => {
+            parts.headers.remove(header::CONTENT_LENGTH);
+            Response::from_parts(parts, Body::from(pretty))
+        }
+        Err(_) => Response::from_parts(parts, Body::from(bytes)),
+    }
+}
@@ -0,0 +1,16 @@
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct PublicConfig {
+    pub limits: PublicLimits,
+    pub maintenance_mode: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PublicLimits {
+    pub title_max_bytes: usize,
+    pub content_max_bytes: usize,
+    pub max_body_bytes: usize,
+    pub max_batch_ids: usize,
+    pub max_suggestions: i64,
+}
@@ -1,3 +1,11 @@
+pub mod config;
+pub mod render;
 pub mod test;
+pub mod time;
+pub mod version;
 
-pub use test::*;
\ No newline at end of file
+pub use config::*;
+pub use render::*;
+pub use test::*;
+pub use time::*;
+pub use version::*;
\ No newline at end of file
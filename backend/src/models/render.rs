@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+
+use crate::config::FieldLimits;
+use crate::error::AppError;
+
+use super::test::check_max_bytes;
+
+#[derive(Debug, Deserialize)]
+pub struct RenderMarkdownRequest {
+    pub markdown: String,
+}
+
+impl RenderMarkdownRequest {
+    // Markdown previews are test content, so they share its size limit
+    pub fn validate(&self, limits: &FieldLimits) -> Result<(), AppError> {
+        check_max_bytes("markdown", Some(&self.markdown), limits.content_max_bytes)
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct RenderedMarkdown {
+    pub html: String,
+}
@@ -3,7 +3,10 @@ use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
 
-#[derive(Debug, Serialize, Deserialize, FromRow)]
+use crate::config::FieldLimits;
+use crate::error::AppError;
+
+#[derive(Clone, Debug, Serialize, Deserialize, FromRow)]
 pub struct Test {
     pub id: Uuid,
     pub title: String,
@@ -12,6 +15,114 @@ pub struct Test {
     pub updated_at: Option<DateTime<Utc>>,
 }
 
+// Row in the plain GET /api/tests listing: content is cut down to a
+// preview in SQL; GET /api/tests/:id returns it in full
+#[derive(Debug, Serialize, FromRow)]
+pub struct TestSummary {
+    pub id: Uuid,
+    pub title: String,
+    pub content_preview: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+// Description of a model field, used for request validation and served
+// to admin tooling
+#[derive(Clone, Debug, Serialize)]
+pub struct FieldDescriptor {
+    pub name: &'static str,
+    #[serde(rename = "type")]
+    pub field_type: &'static str,
+    pub nullable: bool,
+    pub read_only: bool,
+    // Maximum size in bytes, enforced by validate_fields
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_bytes: Option<usize>,
+}
+
+// Fields of the Test model, with size limits taken from config. Built once
+// into AppState::test_fields.
+pub fn test_fields(limits: &FieldLimits) -> Vec<FieldDescriptor> {
+    vec![
+        FieldDescriptor {
+            name: "id",
+            field_type: "uuid",
+            nullable: false,
+            read_only: true,
+            max_bytes: None,
+        },
+        FieldDescriptor {
+            name: "title",
+            field_type: "string",
+            nullable: false,
+            read_only: false,
+            max_bytes: Some(limits.title_max_bytes),
+        },
+        FieldDescriptor {
+            name: "content",
+            field_type: "string",
+            nullable: true,
+            read_only: false,
+            max_bytes: Some(limits.content_max_bytes),
+        },
+        // Computed from content (first TEST_CONTENT_PREVIEW_CHARS characters),
+        // as returned by the plain list
+        FieldDescriptor {
+            name: "content_preview",
+            field_type: "string",
+            nullable: true,
+            read_only: true,
+            max_bytes: None,
+        },
+        FieldDescriptor {
+            name: "created_at",
+            field_type: "datetime",
+            nullable: true,
+            read_only: true,
+            max_bytes: None,
+        },
+        FieldDescriptor {
+            name: "updated_at",
+            field_type: "datetime",
+            nullable: true,
+            read_only: true,
+            max_bytes: None,
+        },
+    ]
+}
+
+// Resolve a comma-separated `fields` parameter against the model's field
+// descriptors, rejecting unknown names
+pub fn parse_fields<'a>(
+    fields: &str,
+    descriptors: &'a [FieldDescriptor],
+) -> Result<Vec<&'a FieldDescriptor>, AppError> {
+    let mut selected: Vec<&'a FieldDescriptor> = Vec::new();
+
+    for name in fields.split(',').map(str::trim).filter(|name| !name.is_empty()) {
+        let field = descriptors
+            .iter()
+            .find(|field| field.name == name)
+            .ok_or_else(|| AppError::BadRequest(format!("Unknown field: {}", name)))?;
+
+        if !selected.iter().any(|existing| existing.name == field.name) {
+            selected.push(field);
+        }
+    }
+
+    if selected.is_empty() {
+        return Err(AppError::BadRequest("fields must name at least one field".to_string()));
+    }
+
+    Ok(selected)
+}
+
+#[derive(Debug, Serialize)]
+pub struct TestSchema {
+    pub entity: &'static str,
+    pub fields: Vec<FieldDescriptor>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct CreateTestRequest {
     pub title: String,
@@ -22,4 +133,294 @@ pub struct CreateTestRequest {
 pub struct UpdateTestRequest {
     pub title: Option<String>,
     pub content: Option<String>,
-}
\ No newline at end of file
+}
+
+impl CreateTestRequest {
+    pub fn validate(&self, fields: &[FieldDescriptor]) -> Result<(), AppError> {
+        validate_fields(Some(&self.title), self.content.as_deref(), fields)
+    }
+
+    pub fn warnings(&self) -> Vec<ValidationWarning> {
+        warn_fields(Some(&self.title), self.content.as_deref())
+    }
+}
+
+impl UpdateTestRequest {
+    pub fn validate(&self, fields: &[FieldDescriptor]) -> Result<(), AppError> {
+        validate_fields(self.title.as_deref(), self.content.as_deref(), fields)
+    }
+
+    pub fn warnings(&self) -> Vec<ValidationWarning> {
+        warn_fields(self.title.as_deref(), self.content.as_deref())
+    }
+}
+
+// Non-blocking advisory returned alongside a successful write. Unlike
+// validation errors, these never reject the request.
+#[derive(Debug, Serialize)]
+pub struct ValidationWarning {
+    pub field: &'static str,
+    pub code: &'static str,
+    pub message: &'static str,
+}
+
+// Flag valid but suspicious values among whichever writable fields are present
+pub fn warn_fields(title: Option<&str>, content: Option<&str>) -> Vec<ValidationWarning> {
+    let mut warnings = Vec::new();
+
+    if let Some(title) = title {
+        // Scripts without case (e.g. Japanese) are never uppercase, so they don't trip this
+        let letters: Vec<char> = title.chars().filter(|c| c.is_alphabetic()).collect();
+        if letters.len() >= 4 && letters.iter().all(|c| c.is_uppercase()) {
+            warnings.push(ValidationWarning {
+                field: "title",
+                code: "title_all_caps",
+                message: "Title is written entirely in capital letters",
+            });
+        }
+    }
+
+    if let Some(content) = content {
+        if content.trim().is_empty() {
+            warnings.push(ValidationWarning {
+                field: "content",
+                code: "content_blank",
+                message: "Content is empty or only whitespace",
+            });
+        } else if title.is_some_and(|title| title.trim() == content.trim()) {
+            warnings.push(ValidationWarning {
+                field: "content",
+                code: "content_same_as_title",
+                message: "Content is identical to the title",
+            });
+        }
+    }
+
+    warnings
+}
+
+// Test returned from create/update/patch, PUT by title and version
+// restore, with any warnings about the input
+#[derive(Debug, Serialize)]
+pub struct TestWriteResponse {
+    #[serde(flatten)]
+    pub test: Test,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<ValidationWarning>,
+}
+
+// Check whichever writable fields are present against the max_bytes in
+// their descriptors
+pub fn validate_fields(
+    title: Option<&str>,
+    content: Option<&str>,
+    fields: &[FieldDescriptor],
+) -> Result<(), AppError> {
+    check_field_size(fields, "title", title)?;
+    check_field_size(fields, "content", content)
+}
+
+fn check_field_size(fields: &[FieldDescriptor], name: &str, value: Option<&str>) -> Result<(), AppError> {
+    match fields.iter().find(|field| field.name == name).and_then(|field| field.max_bytes) {
+        Some(max_bytes) => check_max_bytes(name, value, max_bytes),
+        None => Ok(()),
+    }
+}
+
+pub(super) fn check_max_bytes(field: &str, value: Option<&str>, max_bytes: usize) -> Result<(), AppError> {
+    match value {
+        Some(value) if value.len() > max_bytes => Err(AppError::UnprocessableEntity(format!(
+            "{} exceeds the maximum size of {} bytes",
+            field, max_bytes
+        ))),
+        _ => Ok(()),
+    }
+}
+
+// Body for PUT /api/tests/by-title/:title; the title comes from the path
+#[derive(Debug, Deserialize)]
+pub struct UpsertTestRequest {
+    pub content: Option<String>,
+}
+
+// Filters shared by GET /api/tests and GET /api/tests/count
+#[derive(Debug, Deserialize)]
+pub struct TestFilterQuery {
+    // Comma-separated list of ids to fetch in a single call
+    pub ids: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ListTestsQuery {
+    #[serde(flatten)]
+    pub filter: TestFilterQuery,
+    // Comma-separated list of fields to return (sparse fieldset)
+    pub fields: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TestCount {
+    pub count: i64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GetTestQuery {
+    pub fields: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SuggestTestsQuery {
+    pub q: Option<String>,
+}
+
+// Minimal projection returned by the title autocomplete endpoint
+#[derive(Debug, Serialize, FromRow)]
+pub struct TestSuggestion {
+    pub id: Uuid,
+    pub title: String,
+}
+
+// A single RFC 6902 JSON Patch operation
+#[derive(Debug, Deserialize)]
+pub struct PatchOperation {
+    pub op: String,
+    pub path: String,
+    pub value: Option<serde_json::Value>,
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{http::StatusCode, response::IntoResponse};
+
+    use super::*;
+    use crate::error::ErrorInfo;
+
+    const LIMITS: FieldLimits = FieldLimits {
+        title_max_bytes: 8,
+        content_max_bytes: 16,
+    };
+
+    fn fields() -> Vec<FieldDescriptor> {
+        test_fields(&LIMITS)
+    }
+
+    // Status and detail of the response an error would produce
+    fn status_and_detail(err: AppError) -> (StatusCode, Option<String>) {
+        let response = err.into_response();
+        let detail = response.extensions().get::<ErrorInfo>().and_then(|info| info.detail.clone());
+        (response.status(), detail)
+    }
+
+    #[test]
+    fn rejects_oversized_content() {
+        let content = "x".repeat(17);
+        let err = validate_fields(Some("title"), Some(&content), &fields()).unwrap_err();
+
+        assert_eq!(
+            status_and_detail(err),
+            (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                Some("content exceeds the maximum size of 16 bytes".to_string())
+            )
+        );
+    }
+
+    #[test]
+    fn rejects_oversized_title() {
+        let err = validate_fields(Some("a long title"), None, &fields()).unwrap_err();
+
+        assert_eq!(
+            status_and_detail(err),
+            (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                Some("title exceeds the maximum size of 8 bytes".to_string())
+            )
+        );
+    }
+
+    #[test]
+    fn limits_are_in_bytes_not_characters() {
+        // 3 characters, 9 bytes in UTF-8
+        assert!(validate_fields(Some("漫画本"), None, &fields()).is_err());
+        assert!(validate_fields(Some("漫画"), None, &fields()).is_ok());
+    }
+
+    #[test]
+    fn accepts_values_at_the_limit_and_absent_fields() {
+        let content = "x".repeat(16);
+        assert!(validate_fields(Some("12345678"), Some(&content), &fields()).is_ok());
+        assert!(validate_fields(None, None, &fields()).is_ok());
+    }
+
+    #[test]
+    fn descriptors_expose_the_size_limits() {
+        let fields = serde_json::to_value(fields()).unwrap();
+
+        assert_eq!(fields[1]["max_bytes"], 8);
+        assert_eq!(fields[2]["max_bytes"], 16);
+        assert!(fields[0].get("max_bytes").is_none());
+    }
+
+    #[test]
+    fn parse_fields_keeps_request_order_and_drops_duplicates() {
+        let descriptors = fields();
+        let fields = parse_fields(" title,id,,title ", &descriptors).unwrap();
+        let names: Vec<&str> = fields.iter().map(|field| field.name).collect();
+
+        assert_eq!(names, ["title", "id"]);
+    }
+
+    #[test]
+    fn parse_fields_rejects_unknown_fields() {
+        let err = parse_fields("id,password", &fields()).unwrap_err();
+
+        assert_eq!(
+            status_and_detail(err),
+            (StatusCode::BAD_REQUEST, Some("Unknown field: password".to_string()))
+        );
+    }
+
+    #[test]
+    fn parse_fields_requires_at_least_one_field() {
+        let err = parse_fields(" , ", &fields()).unwrap_err();
+
+        assert_eq!(status_and_detail(err).0, StatusCode::BAD_REQUEST);
+    }
+
+    fn warning_codes(title: Option<&str>, content: Option<&str>) -> Vec<&'static str> {
+        warn_fields(title, content).into_iter().map(|warning| warning.code).collect()
+    }
+
+    #[test]
+    fn warns_on_all_caps_titles() {
+        assert_eq!(warning_codes(Some("BREAKING NEWS!"), None), ["title_all_caps"]);
+        assert!(warning_codes(Some("Breaking news"), None).is_empty());
+        // Short acronyms and caseless scripts are fine
+        assert!(warning_codes(Some("FAQ"), None).is_empty());
+        assert!(warning_codes(Some("漫画テスト"), None).is_empty());
+    }
+
+    #[test]
+    fn warns_on_blank_content() {
+        assert_eq!(warning_codes(None, Some(" \n\t")), ["content_blank"]);
+        assert!(warning_codes(None, None).is_empty());
+    }
+
+    #[test]
+    fn warns_when_content_repeats_the_title() {
+        assert_eq!(
+            warning_codes(Some("Chapter one"), Some(" Chapter one ")),
+            ["content_same_as_title"]
+        );
+        // Without a title in the request there is nothing to compare against
+        assert!(warning_codes(None, Some("Chapter one")).is_empty());
+    }
+
+    #[test]
+    fn reports_every_warning_that_applies() {
+        assert_eq!(
+            warning_codes(Some("LOUD TITLE"), Some("LOUD TITLE")),
+            ["title_all_caps", "content_same_as_title"]
+        );
+    }
+}
@@ -0,0 +1,10 @@
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct ServerTime {
+    // RFC 3339 UTC timestamp with millisecond precision
+    pub now: String,
+    pub unix_ms: i64,
+    // UTC offset of the server's local timezone, e.g. "+09:00"
+    pub timezone: String,
+}
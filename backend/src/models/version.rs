@@ -0,0 +1,28 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+
+// Snapshot of a test's writable fields as of one write
+#[derive(Debug, Serialize, FromRow)]
+pub struct TestVersion {
+    pub version: i32,
+    pub title: String,
+    pub content: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+// Changes a version made relative to the one before it. For the first
+// version, previous_version is null and every set field is a change.
+#[derive(Debug, Serialize)]
+pub struct TestVersionDiff {
+    pub version: i32,
+    pub previous_version: Option<i32>,
+    pub changes: Vec<FieldChange>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FieldChange {
+    pub field: &'static str,
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
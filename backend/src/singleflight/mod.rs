@@ -0,0 +1,123 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::hash::Hash;
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::OnceCell;
+
+// Coalesces concurrent calls for the same key: the first caller runs the
+// work and every caller that arrives while it is in flight shares its
+// result. Nothing is cached once the call completes.
+pub struct SingleFlight<K, V> {
+    calls: Mutex<HashMap<K, Arc<OnceCell<V>>>>,
+}
+
+impl<K, V> SingleFlight<K, V>
+where
+    K: Eq + Hash + Clone,
+    V: Clone,
+{
+    pub fn new() -> Self {
+        Self {
+            calls: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub async fn run<F, Fut>(&self, key: K, work: F) -> V
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = V>,
+    {
+        let call = self
+            .calls
+            .lock()
+            .expect("single-flight lock poisoned")
+            .entry(key.clone())
+            .or_default()
+            .clone();
+
+        // If the caller running `work` is cancelled, the next waiter runs it
+        let value = call.get_or_init(work).await.clone();
+
+        let mut calls = self.calls.lock().expect("single-flight lock poisoned");
+        if calls.get(&key).is_some_and(|current| Arc::ptr_eq(current, &call)) {
+            calls.remove(&key);
+        }
+
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn concurrent_calls_share_one_run() {
+        let flight = Arc::new(SingleFlight::new());
+        let runs = Arc::new(AtomicUsize::new(0));
+
+        let calls = (0..8).map(|_| {
+            let flight = flight.clone();
+            let runs = runs.clone();
+            tokio::spawn(async move {
+                flight
+                    .run("key", || async move {
+                        runs.fetch_add(1, Ordering::SeqCst);
+                        tokio::time::sleep(Duration::from_millis(50)).await;
+                        42
+                    })
+                    .await
+            })
+        });
+
+        for call in calls.collect::<Vec<_>>() {
+            assert_eq!(call.await.unwrap(), 42);
+        }
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn results_are_not_cached_after_completion() {
+        let flight = SingleFlight::new();
+
+        assert_eq!(flight.run(1, || async { "first" }).await, "first");
+        assert_eq!(flight.run(1, || async { "second" }).await, "second");
+        assert!(flight.calls.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn different_keys_run_independently() {
+        let flight = SingleFlight::new();
+
+        let (a, b) = tokio::join!(
+            flight.run("a", || async { 1 }),
+            flight.run("b", || async { 2 }),
+        );
+
+        assert_eq!((a, b), (1, 2));
+    }
+
+    #[tokio::test]
+    async fn waiter_runs_the_work_if_the_leader_is_cancelled() {
+        let flight = Arc::new(SingleFlight::new());
+
+        let leader = tokio::spawn({
+            let flight = flight.clone();
+            async move { flight.run("key", std::future::pending::<u32>).await }
+        });
+        tokio::time::sleep(Duration::from_millis(10)).await;
+
+        let waiter = tokio::spawn({
+            let flight = flight.clone();
+            async move { flight.run("key", || async { 7 }).await }
+        });
+        tokio::time::sleep(Duration::from_millis(10)).await;
+
+        leader.abort();
+        assert_eq!(waiter.await.unwrap(), 7);
+    }
+}
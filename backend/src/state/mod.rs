@@ -0,0 +1,45 @@
+use std::sync::Arc;
+
+use sqlx::{pool::PoolConnection, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::config::Config;
+use crate::db::{self, DbPool};
+use crate::error::AppError;
+use crate::handlers::health::ReadinessCache;
+use crate::models::{test_fields, FieldDescriptor, Test};
+use crate::singleflight::SingleFlight;
+
+#[derive(Clone)]
+pub struct AppState {
+    pub pool: DbPool,
+    pub config: Arc<Config>,
+    pub readiness: Arc<ReadinessCache>,
+    // Coalesces concurrent GET /api/tests/:id lookups for the same id
+    pub test_reads: Arc<SingleFlight<Uuid, Result<Test, AppError>>>,
+    // Test field descriptors with the configured size limits, shared by
+    // validation, PATCH and GET /api/tests/schema
+    pub test_fields: Arc<[FieldDescriptor]>,
+}
+
+impl AppState {
+    pub fn new(pool: DbPool, config: Config) -> Self {
+        Self {
+            pool,
+            readiness: Arc::new(ReadinessCache::new(config.readiness_cache_ttl)),
+            test_reads: Arc::new(SingleFlight::new()),
+            test_fields: test_fields(&config.field_limits).into(),
+            config: Arc::new(config),
+        }
+    }
+
+    // Acquire a pooled connection, logging slow acquires
+    pub async fn acquire(&self) -> Result<PoolConnection<Postgres>, sqlx::Error> {
+        db::acquire(&self.pool, self.config.db_slow_acquire_threshold).await
+    }
+
+    // Begin a transaction on a pooled connection, logging slow acquires
+    pub async fn begin(&self) -> Result<Transaction<'static, Postgres>, sqlx::Error> {
+        db::begin(&self.pool, self.config.db_slow_acquire_threshold).await
+    }
+}